@@ -1,22 +1,18 @@
 extern crate my_alife;
 extern crate ndarray;
-extern crate ndarray_rand;
-extern crate rand;
 
 use my_alife::algorithm::cellular_automata::cellular_automata;
+use my_alife::algorithm::initial_state::{random, single_seed};
 use my_alife::visualizer::array_visualizer::ArrayVisualizer;
 use ndarray::Array1;
-use ndarray_rand::RandomExt;
-use rand::distributions::Range;
 use std::fmt::Debug;
 
 fn main() -> Result<(), impl Debug> {
     let len = 600;
-    let mut initial_array = Array1::<u32>::zeros(len);
     // 初期値固定
-    // initial_array.slice_mut(s![len / 2]).fill(1);
-    // 初期値ランダム
-    initial_array.assign(&Array1::random(len, Range::new(0, 2)));
+    // let initial_array = single_seed(len);
+    // 初期値ランダム(seedを変えなければ毎回同じ初期値になる)
+    let initial_array = random(len, 0.5, 0);
     let rule = 30;
     let next_state = Array1::<u32>::zeros(len);
     let visualizer = ArrayVisualizer::new(
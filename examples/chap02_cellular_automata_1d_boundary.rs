@@ -0,0 +1,25 @@
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use std::fmt::Debug;
+
+fn main() -> Result<(), impl Debug> {
+    let len = 600;
+    // 右端に1セルだけ置いたルール110は左に向かって成長していく
+    // 開放境界なので、周期境界のように右側から回り込んで干渉することがない
+    let mut initial_array = Array1::<u32>::zeros(len);
+    initial_array[len - 1] = 1;
+    let rule: u8 = 110;
+    let next_state = Array1::<u32>::zeros(len);
+    let visualizer = ArrayVisualizer::new(
+        "Cellular Automata 1d (open boundary)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        600,
+        &initial_array,
+    );
+    visualizer?.draw_loop_by_rule((initial_array, next_state), rule, Boundary::Open)
+}
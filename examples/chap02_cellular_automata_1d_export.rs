@@ -0,0 +1,28 @@
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::initial_state::random;
+use my_alife::visualizer::spacetime_diagram::{gray_colors, SpacetimeDiagram};
+use ndarray::Array1;
+use std::env;
+use std::fmt::Debug;
+
+/// ウィンドウを開かずに時空図をPPMで書き出す
+/// ~~~ShellSession
+/// cargo run --example chap02_cellular_automata_1d_export --release -- 110 600 rule110.ppm 2
+/// ~~~
+fn main() -> Result<(), impl Debug> {
+    let args = env::args().collect::<Vec<_>>();
+    let rule = args.get(1).and_then(|arg| arg.parse::<u8>().ok()).unwrap_or(30);
+    let steps = args.get(2).and_then(|arg| arg.parse::<usize>().ok()).unwrap_or(600);
+    let path = args.get(3).cloned().unwrap_or_else(|| format!("rule{}.ppm", rule));
+    let cell_size = args.get(4).and_then(|arg| arg.parse::<usize>().ok()).unwrap_or(1);
+
+    let len = 600;
+    let initial_array = random(len, 0.5, 0);
+    let mut state = (initial_array, Array1::<u32>::zeros(len));
+    let mut diagram = SpacetimeDiagram::new(len, cell_size, gray_colors(2));
+    diagram.record(&rule, &mut state, steps, Boundary::Periodic);
+    diagram.write_ppm(&path)
+}
@@ -0,0 +1,30 @@
+extern crate my_alife;
+extern crate ndarray;
+extern crate num;
+
+use my_alife::algorithm::generalized_cellular_automata::{CellularAutomataRule, RuleTable};
+use my_alife::algorithm::initial_state::random_states;
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use num::BigUint;
+use std::fmt::Debug;
+
+fn main() -> Result<(), impl Debug> {
+    let len = 600;
+    let radius = 2;
+    let states = 2;
+    let initial_array = random_states(len, states, 0);
+    // 近傍半径2、2状態のルール番号は2^32通りある
+    let rule_number = "1436965290".parse::<BigUint>().unwrap();
+    let rule = RuleTable::from_number(radius, states, &rule_number).unwrap();
+    let next_state = Array1::<u32>::zeros(len);
+    let visualizer = ArrayVisualizer::new(
+        "Cellular Automata 1d (generalized)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        600,
+        &initial_array,
+    );
+    // draw_loopのruleは使わず、closureでRuleTableを捕まえて更新する
+    visualizer?.draw_loop((initial_array, next_state), 0, |state, _, _| rule.update(state))
+}
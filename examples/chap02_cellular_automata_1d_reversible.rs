@@ -0,0 +1,37 @@
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::reversible_cellular_automata::{
+    second_order_cellular_automata, second_order_cellular_automata_backward,
+};
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use std::fmt::Debug;
+
+fn main() -> Result<(), impl Debug> {
+    let len = 600;
+    // 中央付近に小さな模様を置き、1つ前の状態は全て0にする
+    let mut initial_array = Array1::<u32>::zeros(len);
+    for i in 0..8 {
+        initial_array[len / 2 + i] = (i % 3 % 2) as u32;
+    }
+    let previous_state = Array1::<u32>::zeros(len);
+    let rule = 90;
+    let visualizer = ArrayVisualizer::new(
+        "Cellular Automata 1d (reversible)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        600,
+        &initial_array,
+    );
+    // 300フェーズ進めたら300フェーズ戻す。画面の下半分に上半分を鏡に写した模様が現れ、初期状態に戻る
+    let mut step = 0;
+    visualizer?.draw_loop((initial_array, previous_state), rule, |state, rule, _| {
+        if step / 300 % 2 == 0 {
+            second_order_cellular_automata(state, rule, len);
+        } else {
+            second_order_cellular_automata_backward(state, rule, len);
+        }
+        step += 1;
+    })
+}
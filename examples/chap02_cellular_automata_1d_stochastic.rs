@@ -0,0 +1,24 @@
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::initial_state::random;
+use my_alife::algorithm::stochastic_cellular_automata::{StochasticUpdater, UpdateScheme};
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use std::fmt::Debug;
+
+fn main() -> Result<(), impl Debug> {
+    let len = 600;
+    let initial_array = random(len, 0.5, 0);
+    let next_state = Array1::<u32>::zeros(len);
+    // 各セルが確率0.5で更新され、遷移結果が確率0.001で反転するルール110
+    let mut updater = StochasticUpdater::new(110u8, UpdateScheme::Asynchronous(0.5), 0.001, 0);
+    let visualizer = ArrayVisualizer::new(
+        "Cellular Automata 1d (asynchronous)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        600,
+        &initial_array,
+    );
+    visualizer?.draw_loop((initial_array, next_state), 110, |state, _, _| updater.update(state))
+}
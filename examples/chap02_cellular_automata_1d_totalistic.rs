@@ -0,0 +1,24 @@
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::initial_state::random_states;
+use my_alife::algorithm::totalistic_cellular_automata::TotalisticRule;
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use std::fmt::Debug;
+
+fn main() -> Result<(), impl Debug> {
+    let len = 600;
+    let rule = "code 912, k=3, r=1".parse::<TotalisticRule>().unwrap();
+    let initial_array = random_states(len, 3, 0);
+    let next_state = Array1::<u32>::zeros(len);
+    let visualizer = ArrayVisualizer::new(
+        "Cellular Automata 1d (totalistic code 912)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        600,
+        &initial_array,
+    );
+    visualizer?.draw_loop_by_rule((initial_array, next_state), rule, Boundary::Periodic)
+}
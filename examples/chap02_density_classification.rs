@@ -0,0 +1,47 @@
+extern crate failure;
+extern crate my_alife;
+extern crate ndarray;
+extern crate rand;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::density_classification::{
+    evolve, load_rule, random_initial_state, save_rule, EvolutionConfig,
+};
+use my_alife::visualizer::array_visualizer::ArrayVisualizer;
+use ndarray::Array1;
+use rand::thread_rng;
+use std::env;
+
+/// 密度分類問題のルールを進化させてbest_rule.txtに保存し、そのルールを描画する
+/// 引数にルールのファイルを渡すと、進化させずにそのルールを描画する
+/// ~~~ShellSession
+/// cargo run --example chap02_density_classification --release
+/// cargo run --example chap02_density_classification --release -- best_rule.txt
+/// ~~~
+fn main() -> Result<(), failure::Error> {
+    let rule = match env::args().nth(1) {
+        Some(path) => load_rule(path)?,
+        None => {
+            let result = evolve(&EvolutionConfig::default(), |record| {
+                println!(
+                    "generation {:3}: best {:.2}, mean {:.2}",
+                    record.generation, record.best_fitness, record.mean_fitness
+                );
+            });
+            save_rule("best_rule.txt", &result.best_rule)?;
+            result.best_rule
+        }
+    };
+
+    let len = 149;
+    let initial_array = random_initial_state(&mut thread_rng(), len);
+    let next_state = Array1::<u32>::zeros(len);
+    let visualizer = ArrayVisualizer::new(
+        "Density Classification",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        320,
+        &initial_array,
+    )?;
+    visualizer.draw_loop_by_rule((initial_array, next_state), rule, Boundary::Periodic)
+}
@@ -0,0 +1,73 @@
+extern crate my_alife;
+extern crate rand;
+
+use my_alife::algorithm::bit_packed_game_of_life::{
+    bit_packed_game_of_life, bit_packed_game_of_life_by_rayon, BitPackedGrid,
+};
+use my_alife::algorithm::game_of_life::game_of_life_by_rayon;
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::life_rule::LifeRule;
+use rand::{Rng, SeedableRng, StdRng};
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 4096;
+const GENERATIONS: usize = 20;
+
+/// 4096x4096の周期境界の盤面で、`game_of_life_by_rayon`とbit演算版の速さを比べる
+///
+/// `cargo run --release --example chap02_game_of_life_benchmark`で実行する
+fn main() {
+    let mut rng: StdRng = SeedableRng::from_seed(&[0][..]);
+    let mut initial_state = Grid::new(SIZE, SIZE);
+    for cell in initial_state.cells_mut() {
+        *cell = rng.gen_range(0, 2);
+    }
+    let rule = LifeRule::conway();
+
+    let mut state = initial_state.clone();
+    let by_rayon = measure(|| state = game_of_life_by_rayon(&state));
+
+    let mut packed = (
+        BitPackedGrid::from_grid(&initial_state),
+        BitPackedGrid::zeros(SIZE, SIZE),
+    );
+    let bit_packed = measure(|| bit_packed_game_of_life(&mut packed, rule));
+
+    let mut packed_by_rayon = (
+        BitPackedGrid::from_grid(&initial_state),
+        BitPackedGrid::zeros(SIZE, SIZE),
+    );
+    let bit_packed_by_rayon = measure(|| bit_packed_game_of_life_by_rayon(&mut packed_by_rayon, rule));
+
+    assert_eq!(packed.0.to_grid(), state);
+    assert_eq!(packed_by_rayon.0, packed.0);
+
+    println!("{}x{} torus, {} generations", SIZE, SIZE, GENERATIONS);
+    for &(name, elapsed) in [
+        ("game_of_life_by_rayon", by_rayon),
+        ("bit_packed_game_of_life", bit_packed),
+        ("bit_packed_game_of_life_by_rayon", bit_packed_by_rayon),
+    ]
+    .iter()
+    {
+        println!(
+            "{:<34}{:>10.1} ms/generation ({:.1}x)",
+            name,
+            millis(elapsed) / GENERATIONS as f64,
+            millis(by_rayon) / millis(elapsed)
+        );
+    }
+}
+
+/// `step`を`GENERATIONS`回呼ぶのにかかった時間
+fn measure<F: FnMut()>(mut step: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..GENERATIONS {
+        step();
+    }
+    start.elapsed()
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs() as f64 * 1000.0 + duration.subsec_nanos() as f64 / 1_000_000.0
+}
@@ -0,0 +1,30 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::game_of_life::game_of_life;
+use my_alife::visualizer::game_of_life_visualizer::GameOfLifeVisualizer;
+use std::env;
+
+/// `cargo run --example chap02_game_of_life_board -- 60 160 0.3 42`のように
+/// 盤面の縦と横のセルの数、生きているセルの割合、乱数のseedを指定する
+///
+/// 省略した場合は縦60、横160、割合0.3で、seedは毎回ランダムに選ぶ
+fn main() -> Result<(), failure::Error> {
+    let args = env::args().collect::<Vec<_>>();
+    let height = args.get(1).map_or(Ok(60), |arg| arg.parse::<usize>())?;
+    let width = args.get(2).map_or(Ok(160), |arg| arg.parse::<usize>())?;
+    let density = args.get(3).map_or(Ok(0.3), |arg| arg.parse::<f64>())?;
+    let seed = match args.get(4) {
+        Some(seed) => Some(seed.parse::<usize>()?),
+        None => None,
+    };
+    let visualizer = GameOfLifeVisualizer::with_random_state(
+        &format!("Game Of Life ({}x{})", width, height),
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        (height, width),
+        density,
+        seed,
+    )?;
+    visualizer.draw_loop(game_of_life)
+}
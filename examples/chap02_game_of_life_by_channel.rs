@@ -1,13 +1,20 @@
+extern crate failure;
 extern crate my_alife;
 
 use my_alife::visualizer::game_of_life_visualizer::GameOfLifeVisualizer;
-use std::fmt::Debug;
+use std::env;
 
-fn main() -> Result<(), impl Debug> {
+/// `cargo run --example chap02_game_of_life_by_channel -- 4`のように、1回描画する毎に進める世代数を指定する
+/// 省略した場合は1世代
+fn main() -> Result<(), failure::Error> {
+    let steps_per_frame = match env::args().nth(1) {
+        Some(steps) => steps.parse::<usize>()?,
+        None => 1,
+    };
     let visualizer = GameOfLifeVisualizer::new(
         "Game Of Life in parallel",
         "res/shaders/matrix_visualizer_vertex.glsl",
         "res/shaders/matrix_visualizer_fragment.glsl",
-    );
-    visualizer?.draw_loop_parallel_by_channel()
+    )?;
+    visualizer.draw_loop_parallel_by_channel_with_steps(steps_per_frame)
 }
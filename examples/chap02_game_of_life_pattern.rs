@@ -0,0 +1,36 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::game_of_life::game_of_life_with_rule;
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::life_pattern::{save_rle, Pattern};
+use my_alife::algorithm::life_rule::LifeRule;
+use my_alife::visualizer::game_of_life_visualizer::GameOfLifeVisualizer;
+use std::env;
+
+/// `cargo run --example chap02_game_of_life_pattern -- path/to/pattern.rle`のようにパターンのファイルを指定する
+/// 省略した場合はグライダー銃。ウィンドウを閉じると最後の状態を`last_state.rle`に保存する
+fn main() -> Result<(), failure::Error> {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "res/patterns/gosper_glider_gun.rle".to_string());
+    let pattern = Pattern::load(&path)?;
+    // パターンにルールが書いていなければB3/S23
+    let rule = pattern.rule().unwrap_or_else(LifeRule::conway);
+    let mut state = Grid::new(100, 100);
+    pattern.place(&mut state, 10, 10)?;
+
+    let visualizer = GameOfLifeVisualizer::from_grid(
+        &format!("Game Of Life ({}, {})", pattern.name().unwrap_or(&path), rule),
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+        state,
+    )?;
+    let mut last_state = Grid::new(100, 100);
+    visualizer.draw_loop(|state, next_state| {
+        game_of_life_with_rule(state, next_state, rule, Boundary::Fixed(0));
+        last_state.clone_from(state);
+    })?;
+    save_rle("last_state.rle", &last_state, rule)
+}
@@ -0,0 +1,23 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::game_of_life::game_of_life_with_rule;
+use my_alife::algorithm::life_rule::LifeRule;
+use my_alife::visualizer::game_of_life_visualizer::GameOfLifeVisualizer;
+use std::env;
+
+/// `cargo run --example chap02_game_of_life_rule -- B36/S23`のようにルールを指定する
+/// 省略した場合はHighLife(B36/S23)
+fn main() -> Result<(), failure::Error> {
+    let rule = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "B36/S23".to_string())
+        .parse::<LifeRule>()?;
+    let visualizer = GameOfLifeVisualizer::new(
+        &format!("Game Of Life ({})", rule),
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+    )?;
+    visualizer.draw_loop(|state, next_state| game_of_life_with_rule(state, next_state, rule, Boundary::Periodic))
+}
@@ -0,0 +1,103 @@
+extern crate my_alife;
+extern crate rayon;
+
+use my_alife::algorithm::game_of_life::{game_of_life, game_of_life_by_rayon, LifeWorkerPool};
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::life_rule::LifeRule;
+use std::mem;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// 1つの実装を測る最短の時間
+const MIN_DURATION: Duration = Duration::from_secs(1);
+
+/// 盤面の大きさごとに、1秒あたりに計算できる世代数とセル数を比べる
+///
+/// `cargo run --release --example chap02_game_of_life_throughput`で実行する
+fn main() {
+    let rule = LifeRule::conway();
+    // `game_of_life_in_parallel`と同じくCPUの数だけworkerを作る
+    let mut pool = LifeWorkerPool::new(rayon::current_num_threads());
+    println!("{} workers", pool.workers());
+    for &size in [64, 256, 1024].iter() {
+        let initial_state = Grid::random(size, size, 0.5, 0);
+        println!("{}x{} torus", size, size);
+
+        let mut state = initial_state.clone();
+        let mut next_state = Grid::new(size, size);
+        report(
+            "game_of_life",
+            size,
+            measure(|| game_of_life(&mut state, &mut next_state)),
+        );
+
+        let mut state = Arc::new(initial_state.clone());
+        let generations = measure(|| state = Arc::new(thread_per_row(state.clone(), rule)));
+        report("thread per row (before)", size, generations);
+        // Arcを他に持っている人がいないので、古い状態のGridが次の世代の書き込み先に使い回される
+        let mut state = Arc::new(initial_state.clone());
+        let pooled = measure(|| {
+            let current_state = mem::replace(&mut state, Arc::new(Grid::new(0, 0)));
+            state = Arc::new(pool.step(current_state, rule));
+        });
+        report("LifeWorkerPool", size, pooled);
+
+        let mut state = initial_state.clone();
+        report(
+            "game_of_life_by_rayon",
+            size,
+            measure(|| state = game_of_life_by_rayon(&state)),
+        );
+
+        // 結果が逐次版と同じか確かめる
+        let (mut state, mut next_state) = (initial_state.clone(), Grid::new(size, size));
+        let mut pooled_state = Arc::new(initial_state);
+        for _ in 0..10 {
+            game_of_life(&mut state, &mut next_state);
+            pooled_state = Arc::new(pool.step(pooled_state, rule));
+        }
+        assert_eq!(*pooled_state, state);
+    }
+}
+
+/// `step`を`MIN_DURATION`以上かかるまで繰り返し、(世代数, かかった時間)を返す
+fn measure<F: FnMut()>(mut step: F) -> (u64, Duration) {
+    let start = Instant::now();
+    let mut generations = 0;
+    while generations < 3 || start.elapsed() < MIN_DURATION {
+        step();
+        generations += 1;
+    }
+    (generations, start.elapsed())
+}
+
+fn report(name: &str, size: usize, (generations, elapsed): (u64, Duration)) {
+    let seconds = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1e9;
+    println!(
+        "  {:<26}{:>10.1} generations/s{:>10.1} Mcells/s",
+        name,
+        generations as f64 / seconds,
+        (generations as usize * size * size) as f64 / seconds / 1e6
+    );
+}
+
+/// 以前の`game_of_life_in_parallel`と同じく、毎世代1行ごとにthreadを作る実装
+fn thread_per_row(state: Arc<Grid>, rule: LifeRule) -> Grid {
+    let (height, width) = state.dims();
+    let handles = (0..height)
+        .map(|i| {
+            let state = state.clone();
+            thread::spawn(move || {
+                (0..width)
+                    .map(|j| {
+                        let sum = state.neighbors(i, j).sum::<u8>();
+                        rule.next_state(state[(i, j)], sum)
+                    })
+                    .collect::<Vec<_>>()
+            })
+        })
+        .collect::<Vec<_>>();
+    let cells = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+    Grid::from_vec(height, width, cells).unwrap()
+}
@@ -0,0 +1,29 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::life_pattern::Pattern;
+use my_alife::algorithm::sparse_life::{SparseLife, Viewport};
+use my_alife::visualizer::game_of_life_visualizer::GameOfLifeVisualizer;
+use std::env;
+
+/// 端のない盤面でRペントミノを動かす。1103世代かけて安定し、その間にグライダーを6つ放出する
+///
+/// `cargo run --example chap02_game_of_life_unbounded -- path/to/pattern.rle follow`のように
+/// パターンのファイルを指定できる。2つ目の引数に`follow`を指定すると、表示する範囲がパターンを追いかける
+fn main() -> Result<(), failure::Error> {
+    let pattern = match env::args().nth(1) {
+        Some(path) => Pattern::load(&path)?,
+        None => Pattern::from_rle("#N R-pentomino\nx = 3, y = 3, rule = B3/S23\nb2o$2o$bo!")?,
+    };
+    let follow = env::args().nth(2).map_or(false, |arg| arg == "follow");
+    let life = SparseLife::from_pattern(&pattern, 0, 0)?;
+    let mut viewport = Viewport::new(0, 0, 200, 200);
+    viewport.center_on(0, 0);
+
+    let visualizer = GameOfLifeVisualizer::new(
+        "Game Of Life (unbounded)",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+    )?;
+    visualizer.draw_loop_unbounded(life, viewport, follow)
+}
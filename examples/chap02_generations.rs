@@ -0,0 +1,32 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::multi_state_automata::{generations, GenerationsRule};
+use my_alife::visualizer::multi_state_visualizer::{generations_palette, MultiStateVisualizer};
+use std::env;
+
+const HEIGHT: usize = 100;
+const WIDTH: usize = 100;
+
+/// `cargo run --example chap02_generations -- 345/2/4`のようにS/B/C記法でルールを指定する
+/// 省略した場合はBrian's Brain(/2/3)
+fn main() -> Result<(), failure::Error> {
+    let rule = match env::args().nth(1) {
+        Some(rule) => rule.parse::<GenerationsRule>()?,
+        None => GenerationsRule::brians_brain(),
+    };
+    let state = Grid::random(HEIGHT, WIDTH, 0.3, 0);
+
+    let visualizer = MultiStateVisualizer::new(
+        &format!("Generations ({})", rule),
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_color_fragment.glsl",
+        state,
+        generations_palette(rule.states()),
+    )?;
+    visualizer.draw_loop(|state, next_state| {
+        generations(state, next_state, rule, Boundary::Periodic);
+    })
+}
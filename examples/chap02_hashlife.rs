@@ -0,0 +1,45 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::hashlife::HashLife;
+use my_alife::algorithm::life_pattern::{save_rle, Pattern};
+use std::env;
+use std::time::Instant;
+
+/// `cargo run --release --example chap02_hashlife -- path/to/pattern.rle 30`のように
+/// パターンのファイルと、何世代進めるかのlog2を指定する(省略した場合はグライダー銃を2^30世代)
+fn main() -> Result<(), failure::Error> {
+    let path = env::args()
+        .nth(1)
+        .unwrap_or_else(|| "res/patterns/gosper_glider_gun.rle".to_string());
+    let log2 = match env::args().nth(2) {
+        Some(log2) => log2.parse::<u8>()?,
+        None => 30,
+    };
+    let pattern = Pattern::load(&path)?;
+    let mut life = HashLife::from_pattern(&pattern, 0, 0)?;
+
+    for _ in 0..log2 + 1 {
+        let start = Instant::now();
+        let generation = life.generation();
+        // 2^0, 2^0, 2^1, 2^2, ...世代ずつ進めて、進めた後の世代が2のべき乗になるようにする
+        life.step(generation.max(1));
+        let elapsed = start.elapsed();
+        println!(
+            "generation {:>20}: population {:>20}, nodes {:>10}, {}.{:03}s",
+            life.generation(),
+            life.population(),
+            life.node_count(),
+            elapsed.as_secs(),
+            elapsed.subsec_millis()
+        );
+    }
+
+    // 最後の状態が小さければRLEで保存する
+    if let Some((top, left, height, width)) = life.bounding_box() {
+        if height * width <= 1 << 20 {
+            save_rle("hashlife.rle", &life.to_grid(top, left, height, width), life.rule())?;
+        }
+    }
+    Ok(())
+}
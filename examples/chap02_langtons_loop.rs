@@ -0,0 +1,34 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::langtons_loop::{initial_loop, langtons_loop, RuleTable};
+use my_alife::visualizer::multi_state_visualizer::{MultiStateVisualizer, LANGTONS_LOOP_PALETTE};
+
+const HEIGHT: usize = 150;
+const WIDTH: usize = 150;
+
+/// 中央に置いたLangtonのループが自己複製を繰り返してコロニーを作る
+fn main() -> Result<(), failure::Error> {
+    let table = RuleTable::load("res/rules/langtons_loop.table")?;
+    let initial_loop = initial_loop();
+    let (height, width) = initial_loop.dims();
+    let mut state = Grid::new(HEIGHT, WIDTH);
+    for i in 0..height {
+        for j in 0..width {
+            state[((HEIGHT - height) / 2 + i, (WIDTH - width) / 2 + j)] = initial_loop[(i, j)];
+        }
+    }
+
+    let visualizer = MultiStateVisualizer::new(
+        "Langton's Loop",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_color_fragment.glsl",
+        state,
+        LANGTONS_LOOP_PALETTE.to_vec(),
+    )?;
+    visualizer.draw_loop(|state, next_state| {
+        langtons_loop(state, next_state, &table, Boundary::Fixed(0));
+    })
+}
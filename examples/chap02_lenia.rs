@@ -0,0 +1,66 @@
+extern crate failure;
+extern crate my_alife;
+extern crate ndarray;
+extern crate rand;
+
+use my_alife::algorithm::continuous_automata::{orbium, Lenia, SmoothLife, SmoothLifeParams};
+use my_alife::visualizer::matrix_visualizer::{Matrix, MatrixVisualizer};
+use my_alife::visualizer::WindowStatus;
+use ndarray::Array2;
+use rand::{thread_rng, Rng};
+use std::env;
+
+/// 縦横を2の累乗にしてFFTで畳み込む
+const SIZE: usize = 128;
+
+/// 盤面を1ステップ進める関数
+type Update = Box<dyn Fn(&mut Matrix<f32>)>;
+
+/// `cargo run --example chap02_lenia -- smoothlife`のようにモデルを指定する
+/// * `orbium` - LeniaのOrbium(省略した場合)
+/// * `smoothlife` - ランダムに正方形を置いた初期状態からのSmoothLife
+fn main() -> Result<(), failure::Error> {
+    let model = env::args().nth(1).unwrap_or_else(|| "orbium".to_string());
+    let mut state = Array2::<f32>::zeros((SIZE, SIZE));
+    let (title, update): (String, Update) = match model.as_str() {
+        "orbium" => {
+            let orbium = orbium();
+            orbium.place(&mut state, SIZE / 2, SIZE / 2);
+            let lenia = Lenia::new(orbium.params.clone(), (SIZE, SIZE));
+            (
+                format!("Lenia ({})", orbium.name),
+                Box::new(move |state| lenia.step(state)),
+            )
+        }
+        "smoothlife" => {
+            let params = SmoothLifeParams::default();
+            // 近傍の輪と同じくらいの大きさの正方形をランダムに置く
+            let side = params.outer_radius as usize;
+            let mut rng = thread_rng();
+            for _ in 0..SIZE * SIZE / (side * side) / 2 {
+                let (top, left) = (rng.gen_range(0, SIZE), rng.gen_range(0, SIZE));
+                for i in 0..side {
+                    for j in 0..side {
+                        state[((top + i) % SIZE, (left + j) % SIZE)] = 1.0;
+                    }
+                }
+            }
+            let smooth_life = SmoothLife::new(params, (SIZE, SIZE));
+            ("SmoothLife".to_string(), Box::new(move |state| smooth_life.step(state)))
+        }
+        _ => return Err(failure::err_msg(format!("unknown model \"{}\"", model))),
+    };
+
+    let mut visualizer = MatrixVisualizer::new(
+        &title,
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_fragment.glsl",
+    )?;
+    let mut window_status = WindowStatus::Open;
+    while window_status == WindowStatus::Open {
+        update(&mut state);
+        visualizer.draw(&state)?;
+        window_status = visualizer.hadling_event();
+    }
+    Ok(())
+}
@@ -0,0 +1,44 @@
+extern crate failure;
+extern crate my_alife;
+extern crate ndarray;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::initial_state::random;
+use my_alife::algorithm::rule_survey::{survey, to_csv, to_json, SurveyConfig};
+use my_alife::visualizer::spacetime_diagram::SpacetimeDiagram;
+use ndarray::Array1;
+use std::env;
+use std::fs::File;
+use std::io::Write;
+
+/// 256通りの基本ルールを調査してrule_survey.csvとrule_survey.jsonに書き出す
+/// 引数にファイル名を渡すと全ルールの時空図を16 * 16に並べた画像も書き出す
+/// ~~~ShellSession
+/// cargo run --example chap02_rule_survey --release -- rule_survey.ppm
+/// ~~~
+fn main() -> Result<(), failure::Error> {
+    let config = SurveyConfig::default();
+    let statistics = survey(&config);
+    for s in &statistics {
+        println!("rule {:3}: class {}", s.rule, s.class);
+    }
+    File::create("rule_survey.csv")?.write_all(to_csv(&statistics).as_bytes())?;
+    File::create("rule_survey.json")?.write_all(to_json(&statistics).as_bytes())?;
+
+    if let Some(path) = env::args().nth(1) {
+        let len = 64;
+        let initial_array = random(len, 0.5, config.seed);
+        // 状態2は時空図の区切りの色
+        let colors = vec![[255, 255, 255], [0, 0, 0], [255, 0, 0]];
+        let diagrams = (0..256)
+            .map(|rule| {
+                let mut state = (initial_array.clone(), Array1::<u32>::zeros(len));
+                let mut diagram = SpacetimeDiagram::new(len, 1, colors.clone());
+                diagram.record(&(rule as u8), &mut state, len - 1, Boundary::Periodic);
+                diagram
+            })
+            .collect::<Vec<_>>();
+        SpacetimeDiagram::tile(&diagrams, 16, 2).write_ppm(&path)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,31 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::life_rule::LifeRule;
+use my_alife::algorithm::soup_census::{soup_census, SoupConfig};
+use std::env;
+use std::fs::File;
+use std::io::prelude::*;
+
+/// `cargo run --release --example chap02_soup_census -- 1000 B3/S23`のように
+/// soupの数とルールを指定する(省略した場合はB3/S23で1000個)
+///
+/// 16x16のランダムなsoupが安定した後に残った物体を数えて、結果を`census.txt`にも保存する
+fn main() -> Result<(), failure::Error> {
+    let soups = match env::args().nth(1) {
+        Some(soups) => soups.parse::<usize>()?,
+        None => 1000,
+    };
+    let rule = env::args()
+        .nth(2)
+        .unwrap_or_else(|| "B3/S23".to_string())
+        .parse::<LifeRule>()?;
+    let config = SoupConfig {
+        soups: soups,
+        ..SoupConfig::default()
+    };
+    let report = soup_census(rule, &config)?.report();
+    print!("{}", report);
+    File::create("census.txt")?.write_all(report.as_bytes())?;
+    Ok(())
+}
@@ -0,0 +1,79 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::grid::Grid;
+use my_alife::algorithm::sparse_life::Viewport;
+use my_alife::algorithm::turmite::{Direction, Turmite, TurmiteRule, TurmiteWorld, UnboundedGrid};
+use my_alife::visualizer::multi_state_visualizer::{turmite_palette, MultiStateVisualizer};
+use std::env;
+
+const HEIGHT: usize = 150;
+const WIDTH: usize = 150;
+/// 1フレームで進めるステップ数
+const STEPS_PER_FRAME: usize = 50;
+
+/// `cargo run --example chap02_turmite -- LLRR unbounded`のようにルールと盤面を指定する
+/// * ルールは`RL`のような文字列かGollyの記法。省略した場合はラングトンのアリ(RL)
+/// * 盤面は`torus`(周期境界)か`unbounded`(端のない盤面)。省略した場合は`torus`
+///
+/// ラングトンのアリのときは、ハイウェイを作り始めたステップ数を表示する
+fn main() -> Result<(), failure::Error> {
+    let rule = match env::args().nth(1) {
+        Some(rule) => rule.parse::<TurmiteRule>()?,
+        None => TurmiteRule::langtons_ant(),
+    };
+    let world = env::args().nth(2).unwrap_or_else(|| "torus".to_string());
+    let unbounded = match world.as_str() {
+        "torus" => false,
+        "unbounded" => true,
+        _ => return Err(failure::err_msg(format!("unknown world \"{}\"", world))),
+    };
+    if rule == TurmiteRule::langtons_ant() {
+        let mut ant = Turmite::new(rule.clone(), 0, 0, Direction::North);
+        if let Some(highway) = ant.run_until_highway(&mut UnboundedGrid::new(), 20000, 200) {
+            println!(
+                "the ant starts building a highway at step {} (period {}, displacement {:?})",
+                highway.start, highway.period, highway.displacement
+            );
+        }
+    }
+
+    let colors = rule.colors();
+    let visualizer = MultiStateVisualizer::new(
+        &format!("Turmite ({})", rule),
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_color_fragment.glsl",
+        Grid::new(HEIGHT, WIDTH),
+        turmite_palette(colors),
+    )?;
+    let (center_i, center_j) = ((HEIGHT / 2) as i64, (WIDTH / 2) as i64);
+    let mut turmite = Turmite::new(rule, center_i, center_j, Direction::North);
+    if unbounded {
+        let mut world = UnboundedGrid::new();
+        let mut viewport = Viewport::new(0, 0, HEIGHT, WIDTH);
+        visualizer.draw_loop(|state, _| {
+            for _ in 0..STEPS_PER_FRAME {
+                turmite.step(&mut world);
+            }
+            // 塗られた範囲全体が見えるように中心を合わせる
+            if let Some((top, left, height, width)) = world.bounding_box() {
+                viewport.center_on(top + height as i64 / 2, left + width as i64 / 2);
+            }
+            *state = world.to_grid(&viewport);
+            let (i, j) = turmite.position();
+            if let Some(position) = viewport.to_local(i, j) {
+                state[position] = colors;
+            }
+        })
+    } else {
+        let mut world = Grid::new(HEIGHT, WIDTH);
+        visualizer.draw_loop(|state, _| {
+            for _ in 0..STEPS_PER_FRAME {
+                turmite.step(&mut world);
+            }
+            state.clone_from(&world);
+            let (i, j) = turmite.position();
+            state.set_color(i, j, colors);
+        })
+    }
+}
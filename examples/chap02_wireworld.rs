@@ -0,0 +1,34 @@
+extern crate failure;
+extern crate my_alife;
+
+use my_alife::algorithm::boundary::Boundary;
+use my_alife::algorithm::multi_state_automata::{parse_wireworld, wireworld};
+use my_alife::visualizer::multi_state_visualizer::{MultiStateVisualizer, WIREWORLD_PALETTE};
+
+/// 左上のループを周る電子が16世代ごとに右へ電子を送り出す。
+/// 上の枝のダイオードは左から右へ電子を通し、下の枝のダイオードは逆向きなので電子を通さない
+const CIRCUIT: &str = "
+ tH#####
+#       #         ##
+ ####### ########## ######
+        #         ##
+        #
+        #          ##
+         ########## ######
+                   ##
+";
+
+fn main() -> Result<(), failure::Error> {
+    let state = parse_wireworld(CIRCUIT.trim_matches('\n'))?;
+
+    let visualizer = MultiStateVisualizer::new(
+        "Wireworld",
+        "res/shaders/matrix_visualizer_vertex.glsl",
+        "res/shaders/matrix_visualizer_color_fragment.glsl",
+        state,
+        WIREWORLD_PALETTE.to_vec(),
+    )?;
+    visualizer.draw_loop(|state, next_state| {
+        wireworld(state, next_state, Boundary::Fixed(0));
+    })
+}
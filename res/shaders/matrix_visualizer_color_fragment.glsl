@@ -0,0 +1,9 @@
+#version 140
+
+uniform sampler2D u_texture;
+in vec2 v_texcoord;
+out vec4 flagColor;
+void main()
+{
+    flagColor = vec4(texture(u_texture, v_texcoord).rgb, 1);
+}
@@ -0,0 +1,135 @@
+use ndarray::Array1;
+use std::mem;
+
+const WORD_BITS: usize = 64;
+
+/// 1セルを1bitとして、64セルずつ`u64`に詰めた1次元配列
+///
+/// セル`i`は`words[i / 64]`の下から`i % 64`bit目に入る。
+/// `Array1<u32>`で1セル1要素持つより、メモリは1/32で済む
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitPackedArray {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl BitPackedArray {
+    /// 全てのセルが0のBitPackedArrayを生成する
+    ///
+    /// # Arguments
+    /// * `len` - セルの数
+    pub fn zeros(len: usize) -> BitPackedArray {
+        BitPackedArray {
+            words: vec![0; (len + WORD_BITS - 1) / WORD_BITS],
+            len: len,
+        }
+    }
+
+    /// `Array1<u32>`から生成する。0以外のセルは1とみなす
+    pub fn from_array(array: &Array1<u32>) -> BitPackedArray {
+        let mut packed = BitPackedArray::zeros(array.len());
+        for (i, cell) in array.iter().enumerate() {
+            if *cell != 0 {
+                packed.words[i / WORD_BITS] |= 1 << (i % WORD_BITS);
+            }
+        }
+        packed
+    }
+
+    /// `Array1<u32>`に戻す
+    pub fn to_array(&self) -> Array1<u32> {
+        Array1::from_vec((0..self.len).map(|i| self.get(i)).collect())
+    }
+
+    /// セルの数
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// セルが1つもないかどうか
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// セル`i`の状態
+    pub fn get(&self, i: usize) -> u32 {
+        ((self.words[i / WORD_BITS] >> (i % WORD_BITS)) & 1) as u32
+    }
+
+    /// 状態1のセルの数
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// `cellular_automata`のbit演算版
+///
+/// 64セル分の`u64`をまとめて左右にshiftし、左・中央・右の3つのwordのbit演算で
+/// 64セル分の次の状態を一度に計算する。ルールの8通りの近傍パターンのうち、次の状態が1になるものについて
+/// 「左が`l`、中央が`c`、右が`r`」を満たすbitを`&`で求め、それらを`|`で足し合わせる。
+/// 境界条件は`cellular_automata`と同じく周期境界で、stateとnext_stateを入れ替える
+///
+/// # Arguments
+/// * `state` - 現在の状態と次の状態の組。2つの長さは同じでなければならない
+/// * `rule` - ウルフラムのルールコーディングの数字
+///
+/// # Example
+/// 全256ルールで`cellular_automata`と同じ結果になる
+/// ```
+/// extern crate ndarray;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::bit_packed_cellular_automata::{bit_packed_cellular_automata, BitPackedArray};
+/// use my_alife::algorithm::cellular_automata::cellular_automata;
+/// use ndarray::Array1;
+///
+/// for space_size in [1, 63, 64, 65, 150].iter().cloned() {
+///     let initial = Array1::from_vec((0..space_size).map(|i| ((i * 7 + i / 3) % 5 % 2) as u32).collect());
+///     for rule in 0..256 {
+///         let rule = rule as u8;
+///         let mut state = (initial.clone(), Array1::<u32>::zeros(space_size));
+///         let mut packed = (BitPackedArray::from_array(&initial), BitPackedArray::zeros(space_size));
+///         for _ in 0..100 {
+///             cellular_automata(&mut state, rule, space_size);
+///             bit_packed_cellular_automata(&mut packed, rule);
+///             assert_eq!(packed.0.to_array(), state.0);
+///         }
+///     }
+/// }
+/// ```
+pub fn bit_packed_cellular_automata(state: &mut (BitPackedArray, BitPackedArray), rule: u8) {
+    let current_state = &mut state.0;
+    let next_state = &mut state.1;
+    let len = current_state.len;
+    {
+        let words = &current_state.words;
+        let word_count = words.len();
+        for (i, &c) in words.iter().enumerate() {
+            // 最後のwordは64セルに満たないことがある
+            let bits = if i == word_count - 1 {
+                len - WORD_BITS * i
+            } else {
+                WORD_BITS
+            };
+            // 各bitの左隣(1つ小さい番号)のセル。先頭のbitには1つ前のwordの最後のセルが入る
+            let l = (c << 1) | current_state.get((WORD_BITS * i + len - 1) % len) as u64;
+            // 各bitの右隣(1つ大きい番号)のセル。最後のbitには次のwordの先頭のセルが入る
+            let r = (c >> 1) | (current_state.get((WORD_BITS * i + bits) % len) as u64) << (bits - 1);
+            let mut next = 0;
+            for neighbor_cell_code in 0..8 {
+                if (rule >> neighbor_cell_code) & 1 == 1 {
+                    let l_match = if neighbor_cell_code & 4 != 0 { l } else { !l };
+                    let c_match = if neighbor_cell_code & 2 != 0 { c } else { !c };
+                    let r_match = if neighbor_cell_code & 1 != 0 { r } else { !r };
+                    next |= l_match & c_match & r_match;
+                }
+            }
+            // 範囲外のbitは常に0にしておく
+            if bits < WORD_BITS {
+                next &= (1 << bits) - 1;
+            }
+            next_state.words[i] = next;
+        }
+    }
+    mem::swap(current_state, next_state);
+}
@@ -0,0 +1,227 @@
+use algorithm::grid::Grid;
+use algorithm::life_rule::LifeRule;
+use std::mem;
+
+const WORD_BITS: usize = 64;
+
+/// 1セルを1bitとして、各行を64セルずつ`u64`に詰めた2次元配列
+///
+/// セル`(i, j)`は`words[i * words_per_row + j / 64]`の下から`j % 64`bit目に入る。
+/// 各行の最後のwordの範囲外のbitは常に0にしておく
+#[derive(Debug, Clone, PartialEq)]
+pub struct BitPackedGrid {
+    words: Vec<u64>,
+    height: usize,
+    width: usize,
+    words_per_row: usize,
+}
+
+impl BitPackedGrid {
+    /// 全てのセルが0のBitPackedGridを生成する
+    ///
+    /// # Arguments
+    /// * `height` - セルの縦の数
+    /// * `width` - セルの横の数
+    pub fn zeros(height: usize, width: usize) -> BitPackedGrid {
+        let words_per_row = (width + WORD_BITS - 1) / WORD_BITS;
+        BitPackedGrid {
+            words: vec![0; height * words_per_row],
+            height: height,
+            width: width,
+            words_per_row: words_per_row,
+        }
+    }
+
+    /// Gridから生成する。0以外のセルは1とみなす
+    pub fn from_grid(grid: &Grid) -> BitPackedGrid {
+        let mut packed = BitPackedGrid::zeros(grid.height(), grid.width());
+        for (i, row) in grid.rows().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                if *cell != 0 {
+                    packed.words[i * packed.words_per_row + j / WORD_BITS] |= 1 << (j % WORD_BITS);
+                }
+            }
+        }
+        packed
+    }
+
+    /// Gridに戻す
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new(self.height, self.width);
+        for i in 0..self.height {
+            for j in 0..self.width {
+                grid[(i, j)] = self.get(i, j);
+            }
+        }
+        grid
+    }
+
+    /// `(height, width)`
+    pub fn dims(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    /// セル`(i, j)`の状態
+    pub fn get(&self, i: usize, j: usize) -> u8 {
+        cell_in_row(self.row(i), j) as u8
+    }
+
+    /// 状態1のセルの数
+    pub fn population(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    fn row(&self, i: usize) -> &[u64] {
+        &self.words[i * self.words_per_row..(i + 1) * self.words_per_row]
+    }
+}
+
+/// `game_of_life_with_rule`(周期境界)のbit演算版
+///
+/// 64セル分の`u64`をまとめて左右にshiftして周囲8セル分のwordを作り、
+/// 全加算器・半加算器のbit演算で64セル分の「生きている近傍の数」(0から8の4bit)を一度に数える。
+/// 数ごとに誕生・生存の条件に合うbitを`|`で足し合わせて次の状態にする
+///
+/// # Arguments
+/// * `state` - 現在の状態と次の状態の組。2つの大きさは同じでなければならない
+/// * `rule` - B/S記法のルール
+///
+/// # Example
+/// `game_of_life_with_rule`と同じ結果になる
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::bit_packed_game_of_life::*;
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::game_of_life::game_of_life_with_rule;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_rule::LifeRule;
+///
+/// for rule in ["B3/S23", "B36/S23", "B2/S", "B3678/S34678", "B1357/S02468"].iter() {
+///     let rule = rule.parse::<LifeRule>().unwrap();
+///     for &(height, width) in [(1, 1), (3, 5), (8, 64), (7, 65), (5, 130)].iter() {
+///         let cells = (0..height * width).map(|k| (k * 7919 % 13 % 2) as u8).collect();
+///         let mut state = Grid::from_vec(height, width, cells).unwrap();
+///         let mut next_state = Grid::new(height, width);
+///         let mut packed = (BitPackedGrid::from_grid(&state), BitPackedGrid::zeros(height, width));
+///         let mut packed_by_rayon = packed.clone();
+///         for _ in 0..20 {
+///             game_of_life_with_rule(&mut state, &mut next_state, rule, Boundary::Periodic);
+///             bit_packed_game_of_life(&mut packed, rule);
+///             bit_packed_game_of_life_by_rayon(&mut packed_by_rayon, rule);
+///             assert_eq!(packed.0.to_grid(), state);
+///             assert_eq!(packed_by_rayon.0, packed.0);
+///         }
+///     }
+/// }
+/// ```
+pub fn bit_packed_game_of_life(state: &mut (BitPackedGrid, BitPackedGrid), rule: LifeRule) {
+    {
+        let current_state = &state.0;
+        let words_per_row = current_state.words_per_row;
+        for (i, row) in state.1.words.chunks_mut(words_per_row.max(1)).enumerate() {
+            next_row(current_state, i, row, rule);
+        }
+    }
+    mem::swap(&mut state.0, &mut state.1);
+}
+
+/// `bit_packed_game_of_life`を[Rayon](https://docs.rs/rayon/1.0.3/rayon/)で行ごとに並列に計算する
+pub fn bit_packed_game_of_life_by_rayon(state: &mut (BitPackedGrid, BitPackedGrid), rule: LifeRule) {
+    use rayon::prelude::*;
+    {
+        let current_state = &state.0;
+        let words_per_row = current_state.words_per_row;
+        state
+            .1
+            .words
+            .par_chunks_mut(words_per_row.max(1))
+            .enumerate()
+            .for_each(|(i, row)| next_row(current_state, i, row, rule));
+    }
+    mem::swap(&mut state.0, &mut state.1);
+}
+
+/// `i`行目の次の状態を`next`に書き込む
+fn next_row(state: &BitPackedGrid, i: usize, next: &mut [u64], rule: LifeRule) {
+    let height = state.height;
+    let width = state.width;
+    let above = state.row((i + height - 1) % height);
+    let middle = state.row(i);
+    let below = state.row((i + 1) % height);
+
+    // 近傍の数が`n`のときに次が1になるbitを作るためのmask(全bitが1か0)
+    let mut born = [0u64; 9];
+    let mut survives = [0u64; 9];
+    for n in 0..9 {
+        if rule.is_born(n as u8) {
+            born[n] = !0;
+        }
+        if rule.survives(n as u8) {
+            survives[n] = !0;
+        }
+    }
+
+    for (k, next_word) in next.iter_mut().enumerate() {
+        // 最後のwordは64セルに満たないことがある
+        let bits = (width - WORD_BITS * k).min(WORD_BITS);
+        let (nw, n, ne) = shifted_words(above, k, bits, width);
+        let (w, c, e) = shifted_words(middle, k, bits, width);
+        let (sw, s, se) = shifted_words(below, k, bits, width);
+
+        // 8つの1bitの入力を4bit(count3 count2 count1 count0)の数に足し合わせる
+        let (sum_a, carry_a) = full_adder(nw, n, ne);
+        let (sum_b, carry_b) = full_adder(w, e, sw);
+        let (sum_c, carry_c) = half_adder(s, se);
+        let (count0, carry_d) = full_adder(sum_a, sum_b, sum_c);
+        let (twos, fours) = full_adder(carry_a, carry_b, carry_c);
+        let (count1, carry_e) = half_adder(twos, carry_d);
+        let (count2, count3) = half_adder(fours, carry_e);
+
+        let mut word = 0;
+        for count in 0..9 {
+            if born[count] | survives[count] == 0 {
+                continue;
+            }
+            let bit = |position: usize, value: u64| {
+                if (count >> position) & 1 == 1 {
+                    value
+                } else {
+                    !value
+                }
+            };
+            let matched = bit(0, count0) & bit(1, count1) & bit(2, count2) & bit(3, count3);
+            word |= matched & ((!c & born[count]) | (c & survives[count]));
+        }
+        // 範囲外のbitは常に0にしておく
+        if bits < WORD_BITS {
+            word &= (1 << bits) - 1;
+        }
+        *next_word = word;
+    }
+}
+
+/// 行`row`の`k`番目のwordについて、(各bitの左隣のセル, そのまま, 各bitの右隣のセル)を返す
+fn shifted_words(row: &[u64], k: usize, bits: usize, width: usize) -> (u64, u64, u64) {
+    let c = row[k];
+    // 先頭のbitには1つ前のwordの最後のセルが入る
+    let w = (c << 1) | cell_in_row(row, (WORD_BITS * k + width - 1) % width);
+    // 最後のbitには次のwordの先頭のセルが入る
+    let e = (c >> 1) | cell_in_row(row, (WORD_BITS * k + bits) % width) << (bits - 1);
+    (w, c, e)
+}
+
+fn cell_in_row(row: &[u64], j: usize) -> u64 {
+    (row[j / WORD_BITS] >> (j % WORD_BITS)) & 1
+}
+
+/// 3bitの和を(1の位, 2の位)で返す
+fn full_adder(a: u64, b: u64, c: u64) -> (u64, u64) {
+    let ab = a ^ b;
+    (ab ^ c, (a & b) | (ab & c))
+}
+
+/// 2bitの和を(1の位, 2の位)で返す
+fn half_adder(a: u64, b: u64) -> (u64, u64) {
+    (a ^ b, a & b)
+}
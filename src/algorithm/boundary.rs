@@ -0,0 +1,65 @@
+/// セルラーオートマトンの境界条件
+///
+/// 空間の外側(`0..size`の範囲外)の座標のセルをどう扱うかを決める。
+/// 1次元でも2次元でも、軸ごとに`index`で配列内の座標に写して使う
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+///
+/// assert_eq!(Boundary::Periodic.index(-1, 5), Some(4));
+/// assert_eq!(Boundary::Reflecting.index(-1, 5), Some(0));
+/// assert_eq!(Boundary::Reflecting.index(6, 5), Some(3));
+/// assert_eq!(Boundary::Fixed(1).index(5, 5), None);
+/// assert_eq!(Boundary::Fixed(1).outside_value(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    /// 周期境界。両端がつながっている
+    Periodic,
+    /// 固定境界。空間の外側のセルは常に指定した状態
+    Fixed(u32),
+    /// 反射境界。端のセルを軸にして鏡のように折り返す(`-1`は`0`、`size`は`size - 1`になる)
+    Reflecting,
+    /// 開放境界。空間の外側は状態0で、端に状態0以外のセルが近づいたら空間を広げる
+    ///
+    /// 状態0が静止状態(全て0の近傍から0になる)であることを仮定している。
+    /// 大きさが固定の2次元の空間では広げられないので`Fixed(0)`と同じになる
+    Open,
+}
+
+impl Boundary {
+    /// 座標`i`を配列内の座標に写す。空間の外側で固定値を使う場合は`None`を返す
+    ///
+    /// # Arguments
+    /// * `i` - 座標(範囲外でも良い)
+    /// * `size` - 空間の大きさ
+    pub fn index(&self, i: isize, size: usize) -> Option<usize> {
+        let size = size as isize;
+        if 0 <= i && i < size {
+            return Some(i as usize);
+        }
+        match *self {
+            Boundary::Periodic => Some(((i % size + size) % size) as usize),
+            Boundary::Reflecting => {
+                let folded = (i % (2 * size) + 2 * size) % (2 * size);
+                if folded < size {
+                    Some(folded as usize)
+                } else {
+                    Some((2 * size - 1 - folded) as usize)
+                }
+            }
+            Boundary::Fixed(_) | Boundary::Open => None,
+        }
+    }
+
+    /// `index`が`None`を返す座標のセルの状態
+    pub fn outside_value(&self) -> u32 {
+        match *self {
+            Boundary::Fixed(value) => value,
+            _ => 0,
+        }
+    }
+}
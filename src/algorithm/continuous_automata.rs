@@ -0,0 +1,523 @@
+use algorithm::boundary::Boundary;
+use algorithm::fft::fft2;
+use failure;
+use ndarray::Array;
+use num::Complex;
+use visualizer::matrix_visualizer::Matrix;
+
+/// Orbiumの初期状態(20行20列)
+const ORBIUM_CELLS: [[f32; 20]; 20] = [
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.1, 0.14, 0.1, 0.0, 0.0, 0.03, 0.03, 0.0, 0.0, 0.3, 0.0, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.24, 0.3, 0.3, 0.18, 0.14, 0.15, 0.16, 0.15, 0.09, 0.2, 0.0, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.15, 0.34, 0.44, 0.46, 0.38, 0.18, 0.14, 0.11, 0.13, 0.19, 0.18, 0.45, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.06, 0.13, 0.39, 0.5, 0.5, 0.37, 0.06, 0.0, 0.0, 0.0, 0.02, 0.16, 0.68, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.11, 0.17, 0.17, 0.33, 0.4, 0.38, 0.28, 0.14, 0.0, 0.0, 0.0, 0.0, 0.0, 0.18, 0.42, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.09, 0.18, 0.13, 0.06, 0.08, 0.26, 0.32, 0.32, 0.27, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.82, 0.0, 0.0,
+    ],
+    [
+        0.27, 0.0, 0.16, 0.12, 0.0, 0.0, 0.0, 0.25, 0.38, 0.44, 0.45, 0.34, 0.0, 0.0, 0.0, 0.0, 0.0, 0.22, 0.17, 0.0,
+    ],
+    [
+        0.0, 0.07, 0.2, 0.02, 0.0, 0.0, 0.0, 0.31, 0.48, 0.57, 0.6, 0.57, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.49, 0.0,
+    ],
+    [
+        0.0, 0.59, 0.19, 0.0, 0.0, 0.0, 0.0, 0.2, 0.57, 0.69, 0.76, 0.76, 0.49, 0.0, 0.0, 0.0, 0.0, 0.0, 0.36, 0.0,
+    ],
+    [
+        0.0, 0.58, 0.19, 0.0, 0.0, 0.0, 0.0, 0.0, 0.67, 0.83, 0.9, 0.92, 0.87, 0.12, 0.0, 0.0, 0.0, 0.0, 0.22, 0.07,
+    ],
+    [
+        0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.7, 0.93, 1.0, 1.0, 1.0, 0.61, 0.0, 0.0, 0.0, 0.0, 0.18, 0.11,
+    ],
+    [
+        0.0, 0.0, 0.82, 0.0, 0.0, 0.0, 0.0, 0.0, 0.47, 1.0, 1.0, 0.98, 1.0, 0.96, 0.27, 0.0, 0.0, 0.0, 0.19, 0.1,
+    ],
+    [
+        0.0, 0.0, 0.46, 0.0, 0.0, 0.0, 0.0, 0.0, 0.25, 1.0, 1.0, 0.84, 0.92, 0.97, 0.54, 0.14, 0.04, 0.1, 0.21, 0.05,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.4, 0.0, 0.0, 0.0, 0.0, 0.09, 0.8, 1.0, 0.82, 0.8, 0.85, 0.63, 0.31, 0.18, 0.19, 0.2, 0.01,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.36, 0.1, 0.0, 0.0, 0.0, 0.05, 0.54, 0.86, 0.79, 0.74, 0.72, 0.6, 0.39, 0.28, 0.24, 0.13, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.01, 0.3, 0.07, 0.0, 0.0, 0.08, 0.36, 0.64, 0.7, 0.64, 0.6, 0.51, 0.39, 0.29, 0.19, 0.04, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.1, 0.24, 0.14, 0.1, 0.15, 0.29, 0.45, 0.53, 0.52, 0.46, 0.4, 0.31, 0.21, 0.08, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.08, 0.21, 0.21, 0.22, 0.29, 0.36, 0.39, 0.37, 0.33, 0.26, 0.18, 0.09, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.03, 0.13, 0.19, 0.22, 0.24, 0.24, 0.23, 0.18, 0.13, 0.05, 0.0, 0.0, 0.0, 0.0,
+    ],
+    [
+        0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.02, 0.06, 0.08, 0.09, 0.07, 0.05, 0.01, 0.0, 0.0, 0.0, 0.0, 0.0,
+    ],
+];
+
+/// 畳み込みの重み
+///
+/// 大きさ`(2 * radius + 1)`四方で、中心の要素が自分自身のセルの重みになる
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kernel {
+    weights: Matrix<f32>,
+    radius: usize,
+}
+
+impl Kernel {
+    /// 重みの行列からKernelインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `weights` - 重み。縦横とも同じ奇数の大きさでなければならない
+    pub fn new(weights: Matrix<f32>) -> Result<Kernel, failure::Error> {
+        let (height, width) = weights.dim();
+        if height != width || height % 2 == 0 {
+            return Err(failure::err_msg(format!(
+                "a kernel must be a square of odd size, not {}x{}",
+                height, width
+            )));
+        }
+        Ok(Kernel {
+            weights: weights,
+            radius: height / 2,
+        })
+    }
+
+    /// Leniaの同心円状(リング)のKernel
+    ///
+    /// 中心からの距離`d`を`r = d / radius`とし、`r`が1未満の範囲を`peaks`の数のリングに分ける。
+    /// `k`番目のリングの重みは`peaks[k]`倍した`exp(4 - 1 / (x * (1 - x)))`(`x`はリングの中での位置)で、
+    /// 全体の和が1になるように正規化する
+    ///
+    /// # Arguments
+    /// * `radius` - 半径(セルの数)
+    /// * `peaks` - 内側からのリングごとの高さ
+    pub fn ring(radius: usize, peaks: &[f32]) -> Kernel {
+        let rings = peaks.len() as f32;
+        Self::normalized_from_distance(radius, |r| {
+            if r >= 1.0 {
+                return 0.0;
+            }
+            let position = rings * r;
+            let x = position.fract();
+            if x <= 0.0 {
+                return 0.0;
+            }
+            peaks[position as usize] * (4.0 - 1.0 / (x * (1.0 - x))).exp()
+        })
+    }
+
+    /// 半径`outer`の円から半径`inner`の円を除いた輪の中で重みが一定のKernel
+    ///
+    /// SmoothLifeで使う。境界をまたぐセルは輪に含まれる幅の分だけの重みにする(アンチエイリアス)。
+    /// 全体の和が1になるように正規化する
+    ///
+    /// # Arguments
+    /// * `inner` - 内側の半径(0なら円板)
+    /// * `outer` - 外側の半径
+    pub fn annulus(inner: f32, outer: f32) -> Kernel {
+        let radius = outer.ceil() as usize;
+        Self::normalized_from_distance(radius, |r| {
+            let d = r * radius as f32;
+            let inside_outer = (outer + 0.5 - d).clamp(0.0, 1.0);
+            let inside_inner = if inner > 0.0 {
+                (inner + 0.5 - d).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            inside_outer - inside_inner
+        })
+    }
+
+    /// 半径
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// 重み
+    pub fn weights(&self) -> &Matrix<f32> {
+        &self.weights
+    }
+
+    /// 中心からの距離を半径で割った値`r`から重みを決め、和が1になるように正規化する
+    fn normalized_from_distance<F: Fn(f32) -> f32>(radius: usize, weight: F) -> Kernel {
+        let size = 2 * radius + 1;
+        let mut weights = Array::from_shape_fn((size, size), |(i, j)| {
+            let (di, dj) = (i as f32 - radius as f32, j as f32 - radius as f32);
+            weight((di * di + dj * dj).sqrt() / radius.max(1) as f32)
+        });
+        let sum = weights.iter().sum::<f32>();
+        if sum > 0.0 {
+            weights.mapv_inplace(|weight| weight / sum);
+        }
+        Kernel {
+            weights: weights,
+            radius: radius,
+        }
+    }
+}
+
+/// 周期境界の盤面とKernelの畳み込み
+///
+/// 盤面の縦横が2の累乗なら、Kernelをフーリエ変換したものを先に計算しておき、
+/// 盤面をフーリエ変換してかけ合わせてから逆変換する(`O(N log N)`)。
+/// そうでなければKernelの重みを直接足し合わせる(`O(N * radius^2)`)。
+/// どちらの場合も、Kernelの`(i, j)`の重みは相対位置`(i - radius, j - radius)`のセルにかかる
+///
+/// # Example
+/// FFTを使っても直接足し合わせても同じ結果になる
+/// ```
+/// extern crate my_alife;
+/// extern crate ndarray;
+///
+/// use my_alife::algorithm::continuous_automata::{convolve_directly, Convolution, Kernel};
+/// use ndarray::Array;
+///
+/// let kernel = Kernel::ring(5, &[1.0, 0.5]);
+/// let state = Array::from_shape_fn((32, 64), |(i, j)| ((i * 7 + j * 13) % 10) as f32 / 10.0);
+/// let convolution = Convolution::new(&kernel, (32, 64));
+/// assert!(convolution.uses_fft());
+/// let by_fft = convolution.apply(&state);
+/// let directly = convolve_directly(&state, &kernel);
+/// assert!(by_fft.iter().zip(directly.iter()).all(|(a, b)| (a - b).abs() < 1e-5));
+/// assert!(!Convolution::new(&kernel, (30, 64)).uses_fft());
+///
+/// // 対称でないKernel(右隣のセルだけを見る)でも同じになる
+/// let east = Kernel::new(Array::from_shape_vec((3, 3), vec![0., 0., 0., 0., 0., 1., 0., 0., 0.]).unwrap()).unwrap();
+/// let state = Array::from_shape_fn((4, 4), |(_, j)| j as f32);
+/// let by_fft = Convolution::new(&east, (4, 4)).apply(&state);
+/// let directly = convolve_directly(&state, &east);
+/// assert!(by_fft.iter().zip(directly.iter()).all(|(a, b)| (a - b).abs() < 1e-5));
+/// assert!(by_fft.row(0).iter().zip([1.0, 2.0, 3.0, 0.0].iter()).all(|(a, b)| (a - b).abs() < 1e-5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Convolution {
+    kernel: Kernel,
+    shape: (usize, usize),
+    fft_kernel: Option<Vec<Complex<f64>>>,
+}
+
+impl Convolution {
+    /// Convolutionインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `kernel` - 重み
+    /// * `shape` - 盤面の大きさ`(height, width)`
+    pub fn new(kernel: &Kernel, shape: (usize, usize)) -> Convolution {
+        let (height, width) = shape;
+        let fft_kernel = if height.is_power_of_two() && width.is_power_of_two() {
+            // Kernelの中心が(0, 0)に来るように、周期境界で盤面と同じ大きさに広げる。
+            // 積をとると畳み込み(相対位置の符号が逆)になるので、重みを中心について反転させて置く
+            let mut data = vec![Complex::new(0.0, 0.0); height * width];
+            let radius = kernel.radius as isize;
+            for ((i, j), weight) in kernel.weights.indexed_iter() {
+                let y = wrap(radius - i as isize, height);
+                let x = wrap(radius - j as isize, width);
+                data[y * width + x] += Complex::new(f64::from(*weight), 0.0);
+            }
+            fft2(&mut data, height, width, false);
+            Some(data)
+        } else {
+            None
+        };
+        Convolution {
+            kernel: kernel.clone(),
+            shape: shape,
+            fft_kernel: fft_kernel,
+        }
+    }
+
+    /// FFTを使うかどうか
+    pub fn uses_fft(&self) -> bool {
+        self.fft_kernel.is_some()
+    }
+
+    /// 各セルについて、周囲のセルにKernelの重みをかけて足し合わせる
+    ///
+    /// # Arguments
+    /// * `state` - 盤面。大きさは`new`で指定したもの
+    pub fn apply(&self, state: &Matrix<f32>) -> Matrix<f32> {
+        assert_eq!(state.dim(), self.shape);
+        let fft_kernel = match self.fft_kernel {
+            Some(ref fft_kernel) => fft_kernel,
+            None => return convolve_directly(state, &self.kernel),
+        };
+        let (height, width) = self.shape;
+        let mut data = state
+            .iter()
+            .map(|&value| Complex::new(f64::from(value), 0.0))
+            .collect::<Vec<_>>();
+        fft2(&mut data, height, width, false);
+        for (value, weight) in data.iter_mut().zip(fft_kernel.iter()) {
+            *value *= weight;
+        }
+        fft2(&mut data, height, width, true);
+        Array::from_shape_vec((height, width), data.iter().map(|value| value.re as f32).collect()).unwrap()
+    }
+}
+
+/// 周期境界の盤面とKernelの畳み込みを、重みを直接足し合わせて計算する
+pub fn convolve_directly(state: &Matrix<f32>, kernel: &Kernel) -> Matrix<f32> {
+    let (height, width) = state.dim();
+    let radius = kernel.radius as isize;
+    let weights = kernel
+        .weights
+        .indexed_iter()
+        .filter(|&(_, weight)| *weight != 0.0)
+        .map(|((i, j), weight)| (i as isize - radius, j as isize - radius, *weight))
+        .collect::<Vec<_>>();
+    Array::from_shape_fn((height, width), |(i, j)| {
+        weights
+            .iter()
+            .map(|&(di, dj, weight)| weight * state[(wrap(i as isize + di, height), wrap(j as isize + dj, width))])
+            .sum()
+    })
+}
+
+/// Leniaのパラメーター
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeniaParams {
+    /// Kernelの半径(セルの数)
+    pub radius: usize,
+    /// 単位時間あたりのステップ数。1ステップで時間が`1 / time_steps`進む
+    pub time_steps: f32,
+    /// 成長関数の中心
+    pub mu: f32,
+    /// 成長関数の幅
+    pub sigma: f32,
+    /// Kernelのリングごとの高さ
+    pub peaks: Vec<f32>,
+}
+
+/// Leniaの成長関数
+///
+/// 周囲の重み付きの和`u`が`mu`に近いほど成長し(最大1)、離れると減衰する(最小-1)ガウス関数
+pub fn growth(u: f32, mu: f32, sigma: f32) -> f32 {
+    2.0 * (-(u - mu) * (u - mu) / (2.0 * sigma * sigma)).exp() - 1.0
+}
+
+/// 連続な状態と連続な近傍を持つセルラーオートマトンLenia
+///
+/// 各セルは0から1の状態を持ち、リング状のKernelで周囲を畳み込んだ値`u`から
+/// `state += growth(u) / time_steps`で更新する(0から1に収める)。盤面は周期境界
+///
+/// # Example
+/// Orbiumは形を保ったまま動き続ける(50ステップで20セル以上進み、盤面の端はまたがない)
+/// ```
+/// extern crate my_alife;
+/// extern crate ndarray;
+///
+/// use my_alife::algorithm::continuous_automata::{orbium, Lenia};
+/// use ndarray::Array2;
+///
+/// let orbium = orbium();
+/// let mut state = Array2::<f32>::zeros((128, 128));
+/// orbium.place(&mut state, 20, 20);
+/// let lenia = Lenia::new(orbium.params.clone(), state.dim());
+///
+/// let center_of_mass = |state: &Array2<f32>| {
+///     let mass = state.scalar_sum();
+///     let (mut i, mut j) = (0.0, 0.0);
+///     for ((y, x), value) in state.indexed_iter() {
+///         i += y as f32 * value / mass;
+///         j += x as f32 * value / mass;
+///     }
+///     (mass, i, j)
+/// };
+/// let (mass, i, j) = center_of_mass(&state);
+/// for _ in 0..50 {
+///     lenia.step(&mut state);
+/// }
+/// let (next_mass, next_i, next_j) = center_of_mass(&state);
+/// assert!((next_mass - mass).abs() < 0.1 * mass);
+/// assert!((next_i - i).abs() + (next_j - j).abs() > 20.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Lenia {
+    params: LeniaParams,
+    convolution: Convolution,
+}
+
+impl Lenia {
+    /// Leniaインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `params` - パラメーター
+    /// * `shape` - 盤面の大きさ`(height, width)`。縦横が2の累乗ならFFTで畳み込む
+    pub fn new(params: LeniaParams, shape: (usize, usize)) -> Lenia {
+        let convolution = Convolution::new(&Kernel::ring(params.radius, &params.peaks), shape);
+        Lenia {
+            params: params,
+            convolution: convolution,
+        }
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &LeniaParams {
+        &self.params
+    }
+
+    /// 1ステップ進める
+    pub fn step(&self, state: &mut Matrix<f32>) {
+        let potential = self.convolution.apply(state);
+        let dt = 1.0 / self.params.time_steps;
+        let (mu, sigma) = (self.params.mu, self.params.sigma);
+        state.zip_mut_with(&potential, |cell, &u| {
+            *cell = (*cell + dt * growth(u, mu, sigma)).clamp(0.0, 1.0);
+        });
+    }
+}
+
+/// 名前の付いたLeniaの生き物
+#[derive(Debug, Clone, PartialEq)]
+pub struct Creature {
+    /// 名前
+    pub name: &'static str,
+    /// パラメーター
+    pub params: LeniaParams,
+    /// 初期状態
+    pub cells: Matrix<f32>,
+}
+
+impl Creature {
+    /// 初期状態の左上が`(top, left)`に来るように盤面に置く。盤面からはみ出した分は周期境界で折り返す
+    pub fn place(&self, state: &mut Matrix<f32>, top: usize, left: usize) {
+        let (height, width) = state.dim();
+        for ((i, j), value) in self.cells.indexed_iter() {
+            state[((top + i) % height, (left + j) % width)] = *value;
+        }
+    }
+}
+
+/// Orbium unicaudatus。ゆっくり一方向に進む、Leniaで最もよく知られた生き物
+pub fn orbium() -> Creature {
+    Creature {
+        name: "Orbium",
+        params: LeniaParams {
+            radius: 13,
+            time_steps: 10.0,
+            mu: 0.15,
+            sigma: 0.015,
+            peaks: vec![1.0],
+        },
+        cells: Array::from_shape_fn((20, 20), |(i, j)| ORBIUM_CELLS[i][j]),
+    }
+}
+
+/// SmoothLifeのパラメーター
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SmoothLifeParams {
+    /// 内側の円(自分自身)の半径
+    pub inner_radius: f32,
+    /// 外側の輪(近傍)の半径
+    pub outer_radius: f32,
+    /// 誕生する近傍の充填率の範囲
+    pub birth: (f32, f32),
+    /// 生き残る近傍の充填率の範囲
+    pub survival: (f32, f32),
+    /// 近傍の充填率に対するしきい値のなめらかさ
+    pub alpha_n: f32,
+    /// 自分自身の充填率に対するしきい値のなめらかさ
+    pub alpha_m: f32,
+}
+
+impl Default for SmoothLifeParams {
+    /// Raflerの論文のパラメーター。ランダムな初期状態からグライダーが生まれる
+    fn default() -> SmoothLifeParams {
+        SmoothLifeParams {
+            inner_radius: 7.0,
+            outer_radius: 21.0,
+            birth: (0.278, 0.365),
+            survival: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+        }
+    }
+}
+
+/// ライフゲームを連続な状態と連続な近傍に拡張したSmoothLife
+///
+/// 半径`inner_radius`の円の充填率`m`を自分自身の状態、そこから半径`outer_radius`までの輪の充填率`n`を
+/// 近傍の状態とみなし、`m`が0.5より小さければ(死んでいれば)`n`が`birth`の範囲で、
+/// 大きければ(生きていれば)`survival`の範囲で1に近づく関数で次の状態を決める。盤面は周期境界
+///
+/// # Example
+/// 大きな円板の中心は、近傍が混み合いすぎて死ぬ
+/// ```
+/// extern crate my_alife;
+/// extern crate ndarray;
+///
+/// use my_alife::algorithm::continuous_automata::{SmoothLife, SmoothLifeParams};
+/// use ndarray::Array;
+///
+/// let smooth_life = SmoothLife::new(SmoothLifeParams::default(), (64, 64));
+/// let mut state = Array::from_shape_fn((64, 64), |(i, j)| {
+///     let (di, dj) = (i as f32 - 32.0, j as f32 - 32.0);
+///     if di * di + dj * dj < 25.0 * 25.0 { 1.0 } else { 0.0 }
+/// });
+/// smooth_life.step(&mut state);
+/// assert!(state[(32, 32)] < 0.1);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SmoothLife {
+    params: SmoothLifeParams,
+    inner: Convolution,
+    outer: Convolution,
+}
+
+impl SmoothLife {
+    /// SmoothLifeインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `params` - パラメーター
+    /// * `shape` - 盤面の大きさ`(height, width)`。縦横が2の累乗ならFFTで畳み込む
+    pub fn new(params: SmoothLifeParams, shape: (usize, usize)) -> SmoothLife {
+        SmoothLife {
+            params: params,
+            inner: Convolution::new(&Kernel::annulus(0.0, params.inner_radius), shape),
+            outer: Convolution::new(&Kernel::annulus(params.inner_radius, params.outer_radius), shape),
+        }
+    }
+
+    /// パラメーター
+    pub fn params(&self) -> &SmoothLifeParams {
+        &self.params
+    }
+
+    /// 1ステップ進める
+    pub fn step(&self, state: &mut Matrix<f32>) {
+        let m = self.inner.apply(state);
+        let n = self.outer.apply(state);
+        let params = self.params;
+        *state = Array::from_shape_fn(state.dim(), |position| transition(&params, n[position], m[position]));
+    }
+}
+
+/// SmoothLifeの遷移関数
+fn transition(params: &SmoothLifeParams, n: f32, m: f32) -> f32 {
+    let sigmoid = |x: f32, a: f32, alpha: f32| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+    // 自分自身が生きている度合いで誕生と生存のしきい値を混ぜる
+    let alive = sigmoid(m, 0.5, params.alpha_m);
+    let low = params.birth.0 * (1.0 - alive) + params.survival.0 * alive;
+    let high = params.birth.1 * (1.0 - alive) + params.survival.1 * alive;
+    sigmoid(n, low, params.alpha_n) * (1.0 - sigmoid(n, high, params.alpha_n))
+}
+
+/// 座標`i`を周期境界で`0..size`に写す
+fn wrap(i: isize, size: usize) -> usize {
+    Boundary::Periodic.index(i, size).unwrap()
+}
@@ -0,0 +1,280 @@
+use algorithm::generalized_cellular_automata::{CellularAutomataRule, RuleTable};
+use failure;
+use ndarray::Array1;
+use rand::{Rng, SeedableRng, StdRng};
+use rayon::prelude::*;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+/// 密度分類問題で使う近傍半径
+pub const RADIUS: usize = 3;
+
+/// 半径3、2状態のルール表の大きさ(2^7)
+const TABLE_SIZE: usize = 128;
+
+/// 密度分類問題のルールを進化させる遺伝的アルゴリズムの設定
+///
+/// 初期値はMitchellとCrutchfieldの実験に合わせている
+#[derive(Debug, Clone, Copy)]
+pub struct EvolutionConfig {
+    /// 1世代の個体(ルール)の数(1以上)
+    pub population: usize,
+    /// 次の世代にそのまま残す上位の個体の数
+    pub elite: usize,
+    /// 世代数
+    pub generations: usize,
+    /// 1世代で評価に使う初期状態の数
+    pub samples: usize,
+    /// セルの数。密度がちょうど1/2にならないよう奇数にする
+    pub space_size: usize,
+    /// 1回の評価で何フェーズ計算するか
+    pub steps: usize,
+    /// 子供1個体あたりに突然変異させるbitの数
+    pub mutations: usize,
+    /// 乱数のseed
+    pub seed: usize,
+}
+
+impl Default for EvolutionConfig {
+    fn default() -> EvolutionConfig {
+        EvolutionConfig {
+            population: 100,
+            elite: 20,
+            generations: 100,
+            samples: 100,
+            space_size: 149,
+            steps: 320,
+            mutations: 2,
+            seed: 0,
+        }
+    }
+}
+
+/// 1世代分の記録
+#[derive(Debug, Clone)]
+pub struct GenerationRecord {
+    /// 世代番号(0から)
+    pub generation: usize,
+    /// 最も良い個体の適応度
+    pub best_fitness: f64,
+    /// 適応度の平均
+    pub mean_fitness: f64,
+}
+
+/// 進化の結果
+#[derive(Debug, Clone)]
+pub struct EvolutionResult {
+    /// 最後の世代で最も適応度の高かったルール
+    pub best_rule: RuleTable,
+    /// 世代ごとの適応度の記録
+    pub history: Vec<GenerationRecord>,
+}
+
+/// 密度分類問題を解くルールを遺伝的アルゴリズムで進化させる
+///
+/// 1. 半径3のルール表(128bit)をランダムに`population`個作る
+/// 2. 毎世代、密度が一様分布に従う初期状態を`samples`個作り、各ルールが正しく分類できた割合を適応度とする
+/// 3. 上位`elite`個はそのまま残し、残りはeliteから選んだ2個体の一点交叉と`mutations`bitの突然変異で作る
+///
+/// `config.population`が0のときはpanicする
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::density_classification::{evolve, EvolutionConfig};
+///
+/// let config = EvolutionConfig {
+///     population: 10,
+///     elite: 4,
+///     generations: 3,
+///     samples: 10,
+///     space_size: 29,
+///     steps: 60,
+///     ..EvolutionConfig::default()
+/// };
+/// let result = evolve(&config, |_| {});
+/// assert_eq!(result.history.len(), 3);
+/// // 同じseedなら同じルールになる
+/// assert_eq!(evolve(&config, |_| {}).best_rule, result.best_rule);
+/// ```
+///
+/// # Arguments
+/// * `config` - 遺伝的アルゴリズムの設定
+/// * `on_generation` - 1世代の評価が終わるたびに呼ばれる関数(進捗の表示などに使う)
+pub fn evolve<F>(config: &EvolutionConfig, mut on_generation: F) -> EvolutionResult
+where
+    F: FnMut(&GenerationRecord),
+{
+    assert!(config.population > 0, "the population of the evolution must be at least 1");
+    let mut rng: StdRng = SeedableRng::from_seed(&[config.seed][..]);
+    let mut population = (0..config.population)
+        .map(|_| (0..TABLE_SIZE).map(|_| rng.gen_range(0, 2)).collect::<Vec<u32>>())
+        .collect::<Vec<_>>();
+    let mut history = Vec::with_capacity(config.generations);
+    let mut best_rule = population[0].clone();
+
+    for generation in 0..config.generations {
+        let initial_states = (0..config.samples)
+            .map(|_| random_initial_state(&mut rng, config.space_size))
+            .collect::<Vec<_>>();
+        let mut ranked = population
+            .par_iter()
+            .map(|table| {
+                let rule = RuleTable::new(RADIUS, 2, table.clone()).unwrap();
+                (fitness(&rule, &initial_states, config.steps), table.clone())
+            })
+            .collect::<Vec<_>>();
+        // 適応度の高い順に並べる
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+
+        let record = GenerationRecord {
+            generation: generation,
+            best_fitness: ranked[0].0,
+            mean_fitness: ranked.iter().map(|r| r.0).sum::<f64>() / ranked.len() as f64,
+        };
+        on_generation(&record);
+        history.push(record);
+        best_rule = ranked[0].1.clone();
+
+        let elite = ranked
+            .into_iter()
+            .take(config.elite.max(1))
+            .map(|r| r.1)
+            .collect::<Vec<_>>();
+        population = elite.clone();
+        while population.len() < config.population {
+            let mother = &elite[rng.gen_range(0, elite.len())];
+            let father = &elite[rng.gen_range(0, elite.len())];
+            let point = rng.gen_range(1, TABLE_SIZE);
+            for &(first, second) in [(mother, father), (father, mother)].iter() {
+                if population.len() >= config.population {
+                    break;
+                }
+                let mut child = first[..point].to_vec();
+                child.extend_from_slice(&second[point..]);
+                for _ in 0..config.mutations {
+                    let position = rng.gen_range(0, TABLE_SIZE);
+                    child[position] ^= 1;
+                }
+                population.push(child);
+            }
+        }
+    }
+
+    EvolutionResult {
+        best_rule: RuleTable::new(RADIUS, 2, best_rule).unwrap(),
+        history: history,
+    }
+}
+
+/// ルールが初期状態の密度を正しく分類できた割合
+///
+/// 初期状態の状態1のセルが過半数なら全て1、そうでなければ全て0で止まれば正解
+///
+/// # Example
+/// GKLルールは人が設計した代表的なルールで、8割前後の初期状態を正しく分類できる
+/// ```
+/// extern crate rand;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::density_classification::{fitness, gkl_rule, random_initial_state};
+/// use rand::{SeedableRng, StdRng};
+///
+/// let mut rng: StdRng = SeedableRng::from_seed(&[0][..]);
+/// let initial_states = (0..100).map(|_| random_initial_state(&mut rng, 149)).collect::<Vec<_>>();
+/// assert!(fitness(&gkl_rule(), &initial_states, 320) > 0.7);
+/// ```
+pub fn fitness(rule: &RuleTable, initial_states: &[Array1<u32>], steps: usize) -> f64 {
+    let correct = initial_states
+        .iter()
+        .filter(|initial_state| {
+            let majority = if 2 * initial_state.iter().sum::<u32>() as usize > initial_state.len() {
+                1
+            } else {
+                0
+            };
+            classify(rule, initial_state, steps) == Some(majority)
+        })
+        .count();
+    correct as f64 / initial_states.len() as f64
+}
+
+/// `steps`フェーズ以内に全てのセルが同じ状態で止まれば、その状態を返す
+pub fn classify(rule: &RuleTable, initial_state: &Array1<u32>, steps: usize) -> Option<u32> {
+    let mut state = (initial_state.clone(), Array1::<u32>::zeros(initial_state.len()));
+    for _ in 0..steps {
+        rule.update(&mut state);
+        // 変化しなくなったらそれ以上計算しても同じ
+        if state.0 == state.1 {
+            break;
+        }
+    }
+    let first = state.0[0];
+    if state.0.iter().all(|cell| *cell == first) {
+        Some(first)
+    } else {
+        None
+    }
+}
+
+/// 密度が0から1の一様分布に従うランダムな初期状態
+///
+/// 各セルが1になる確率`p`を一様分布から選び、各セルを確率`p`で1にする
+pub fn random_initial_state<R: Rng>(rng: &mut R, space_size: usize) -> Array1<u32> {
+    let density = rng.gen::<f64>();
+    Array1::from_vec(
+        (0..space_size)
+            .map(|_| if rng.gen::<f64>() < density { 1 } else { 0 })
+            .collect(),
+    )
+}
+
+/// Gacs-Kurdyumov-LevinのGKLルール
+///
+/// 中央が0なら中央・左隣・3つ左の多数決、中央が1なら中央・右隣・3つ右の多数決で次の状態を決める
+pub fn gkl_rule() -> RuleTable {
+    let table = (0..TABLE_SIZE)
+        .map(|code| {
+            // 近傍は左端が最上位bit
+            let cell = |offset: isize| ((code >> (RADIUS as isize - offset)) & 1) as u32;
+            let votes = if cell(0) == 0 {
+                cell(0) + cell(-1) + cell(-3)
+            } else {
+                cell(0) + cell(1) + cell(3)
+            };
+            if votes >= 2 {
+                1
+            } else {
+                0
+            }
+        })
+        .collect();
+    RuleTable::new(RADIUS, 2, table).unwrap()
+}
+
+/// 半径3、2状態のルール表を`0`と`1`の128文字としてファイルに保存する
+///
+/// 近傍`0000000`に対する次の状態から順に並べる(MitchellとCrutchfieldの論文と同じ順番)
+pub fn save_rule<P: AsRef<Path>>(path: P, rule: &RuleTable) -> Result<(), failure::Error> {
+    let line = rule.table().iter().map(|cell| cell.to_string()).collect::<String>();
+    File::create(path)?.write_all(format!("{}\n", line).as_bytes())?;
+    Ok(())
+}
+
+/// `save_rule`で保存したルール表を読み込む
+pub fn load_rule<P: AsRef<Path>>(path: P) -> Result<RuleTable, failure::Error> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    let table = contents
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '0' => Ok(0),
+            '1' => Ok(1),
+            _ => Err(failure::err_msg(format!("invalid character '{}' in rule table", c))),
+        })
+        .collect::<Result<Vec<u32>, _>>()?;
+    RuleTable::new(RADIUS, 2, table)
+}
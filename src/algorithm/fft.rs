@@ -0,0 +1,102 @@
+use num::Complex;
+use std::f64::consts::PI;
+
+/// 長さが2の累乗の列の高速フーリエ変換(FFT)
+///
+/// Cooley-Tukeyの基数2のアルゴリズムで、`data`をその場で変換する。
+/// `inverse`がtrueなら逆変換で、結果を長さで割って元の列に戻す
+///
+/// # Arguments
+/// * `data` - 変換する列。長さは2の累乗でなければならない
+/// * `inverse` - trueなら逆変換
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+/// extern crate num;
+///
+/// use my_alife::algorithm::fft::fft;
+/// use num::Complex;
+///
+/// let original = (0..8).map(|k| Complex::new(k as f64, 0.0)).collect::<Vec<_>>();
+/// let mut data = original.clone();
+/// fft(&mut data, false);
+/// // 0番目の成分は総和
+/// assert!((data[0] - Complex::new(28.0, 0.0)).norm() < 1e-9);
+/// fft(&mut data, true);
+/// for (a, b) in data.iter().zip(original.iter()) {
+///     assert!((a - b).norm() < 1e-9);
+/// }
+/// ```
+pub fn fft(data: &mut [Complex<f64>], inverse: bool) {
+    let n = data.len();
+    assert!(
+        n.is_power_of_two(),
+        "the length of FFT must be a power of two, not {}",
+        n
+    );
+
+    // ビット反転した位置と入れ替える
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            data.swap(i, j);
+        }
+    }
+
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut length = 2;
+    while length <= n {
+        let angle = sign * 2.0 * PI / length as f64;
+        let root = Complex::new(angle.cos(), angle.sin());
+        for start in (0..n).step_by(length) {
+            let mut w = Complex::new(1.0, 0.0);
+            for k in 0..length / 2 {
+                let even = data[start + k];
+                let odd = data[start + k + length / 2] * w;
+                data[start + k] = even + odd;
+                data[start + k + length / 2] = even - odd;
+                w *= root;
+            }
+        }
+        length <<= 1;
+    }
+
+    if inverse {
+        for value in data.iter_mut() {
+            *value /= n as f64;
+        }
+    }
+}
+
+/// 2次元の高速フーリエ変換
+///
+/// 行ごとに変換してから列ごとに変換する
+///
+/// # Arguments
+/// * `data` - `height`行`width`列の値を行ごとに並べたもの。`height`と`width`は2の累乗でなければならない
+/// * `height` - 行の数
+/// * `width` - 列の数
+/// * `inverse` - trueなら逆変換
+pub fn fft2(data: &mut [Complex<f64>], height: usize, width: usize, inverse: bool) {
+    assert_eq!(data.len(), height * width);
+    for row in data.chunks_mut(width) {
+        fft(row, inverse);
+    }
+    let mut column = vec![Complex::new(0.0, 0.0); height];
+    for j in 0..width {
+        for (i, value) in column.iter_mut().enumerate() {
+            *value = data[i * width + j];
+        }
+        fft(&mut column, inverse);
+        for (i, value) in column.iter().enumerate() {
+            data[i * width + j] = *value;
+        }
+    }
+}
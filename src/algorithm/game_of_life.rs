@@ -1,41 +1,79 @@
+use algorithm::boundary::Boundary;
+use algorithm::grid::Grid;
+use algorithm::life_rule::LifeRule;
 use std::mem;
 
 /// ライフゲームのアルゴリズム
 /// 現在のstateを元に次の瞬間のstate(next_state)を計算しstateとnext_stateを入れ替える
 /// # Arguments
 /// * `state` - 現在の状態
-/// * `next_state` - 次の瞬間の状態
-/// * `height` - セルの縦の数
-/// * `width` - セルの横の数
-pub fn game_of_life(state: &mut Vec<Vec<u8>>, next_state: &mut Vec<Vec<u8>>, height: usize, width: usize) {
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+pub fn game_of_life(state: &mut Grid, next_state: &mut Grid) {
+    game_of_life_with_boundary(state, next_state, Boundary::Periodic);
+}
+
+/// 境界条件を指定できるライフゲームのアルゴリズム
+/// 縦横それぞれの軸に同じ境界条件を使う。`Boundary::Open`は空間を広げられないので`Boundary::Fixed(0)`と同じになる
+/// # Arguments
+/// * `state` - 現在の状態
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+/// * `boundary` - 境界条件
+pub fn game_of_life_with_boundary(state: &mut Grid, next_state: &mut Grid, boundary: Boundary) {
+    game_of_life_with_rule(state, next_state, LifeRule::conway(), boundary);
+}
+
+/// ルールと境界条件を指定できるライフゲームのアルゴリズム
+/// # Arguments
+/// * `state` - 現在の状態
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+/// * `rule` - B/S記法のルール
+/// * `boundary` - 境界条件
+///
+/// # Example
+/// 2セルの縦棒はB3/S23では消えるが、Seeds(B2/S)では左右に2セルずつ生まれる
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::game_of_life::game_of_life_with_rule;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_rule::LifeRule;
+///
+/// let mut state = Grid::new(5, 5);
+/// state[(1, 2)] = 1;
+/// state[(2, 2)] = 1;
+/// let mut next_state = Grid::new(5, 5);
+/// let seeds = "B2/S".parse::<LifeRule>().unwrap();
+/// game_of_life_with_rule(&mut state, &mut next_state, seeds, Boundary::Fixed(0));
+/// assert_eq!(state.population(), 4);
+/// assert_eq!((state[(1, 1)], state[(1, 3)], state[(2, 1)], state[(2, 3)]), (1, 1, 1, 1));
+/// ```
+pub fn game_of_life_with_rule(state: &mut Grid, next_state: &mut Grid, rule: LifeRule, boundary: Boundary) {
+    let (height, width) = state.dims();
     for i in 0..height {
         for j in 0..width {
-            let nw = state[(i + height - 1) % height][(j + width - 1) % width];
-            let n = state[(i + height - 1) % height][j];
-            let ne = state[(i + height - 1) % height][(j + 1) % width];
-            let w = state[i][(j + width - 1) % width];
-            let c = state[i][j];
-            let e = state[i][(j + 1) % width];
-            let sw = state[(i + 1) % height][(j + width - 1) % width];
-            let s = state[(i + 1) % height][j];
-            let se = state[(i + 1) % height][(j + 1) % width];
-            let neighbor_cell_sum = nw + n + ne + w + e + sw + s + se;
-            if c == 0 && neighbor_cell_sum == 3 {
-                next_state[i][j] = 1;
-            } else if c == 1 && (neighbor_cell_sum == 2 || neighbor_cell_sum == 3) {
-                next_state[i][j] = 1;
-            } else {
-                next_state[i][j] = 0;
-            }
+            let neighbor_cell_sum = state.neighbors_with_boundary(i, j, boundary).sum::<u8>();
+            next_state[(i, j)] = rule.next_state(state[(i, j)], neighbor_cell_sum);
         }
     }
     mem::swap(state, next_state);
 }
 
+use std::cell::RefCell;
+use std::ops::Range;
+use std::sync::mpsc::{channel, Receiver, Sender};
 use std::sync::Arc;
+use std::thread::{self, JoinHandle};
+
+thread_local! {
+    /// `game_of_life_in_parallel`を呼んだthreadごとに1つ作り、世代をまたいで使い回すworker pool
+    static WORKER_POOL: RefCell<Option<LifeWorkerPool>> = RefCell::new(None);
+}
+
 /// ライフゲームのアルゴリズム
 /// 現在のstateを元に次の瞬間のstate(next_state)を計算し返り値として返す
-/// * next_stateを計算するのに、複数threadを用いて各行の値を計算し(fork)、計算結果が集まるのを待つ(join)
+/// * next_stateを計算するのに、盤面を横長の帯に分けて複数threadで計算し(fork)、計算結果が集まるのを待つ(join)
+///   * threadは毎回作らずに`LifeWorkerPool`のものを使い回す(呼び出したthreadごとに最初の1回だけ作る)
 /// * stateを変更することはない
 ///   * stateをimmutableにして複数threadで共有できる
 ///     * [std::sync::Arc(Atomic Reference Counted)](https://doc.rust-lang.org/std/sync/struct.Arc.html)
@@ -43,53 +81,180 @@ use std::sync::Arc;
 ///
 /// # Arguments
 /// * `state` - 現在の状態(Arc(参照のようなもの)に包まれている)
-/// * `height` - セルの縦の数
-/// * `width` - セルの横の数
-pub fn game_of_life_in_parallel(state: Arc<Vec<Vec<u8>>>, height: usize, width: usize) -> Vec<Vec<u8>> {
-    use std::thread;
+pub fn game_of_life_in_parallel(state: Arc<Grid>) -> Grid {
+    game_of_life_in_parallel_with_rule(state, LifeRule::conway())
+}
 
-    let mut thread_handles = vec![];
-    for i in 0..height {
-        // cloneしてもdata自体がcloneされるわけではなく、参照のようなものがcloneされる
-        // 参照のようなものがいくつ使われているかはcountされている(実行時コストがかかる)。countが0になったら中身をdropする
-        let cloned_state = state.clone();
-        // 高さの数だけthreadを生成する
-        thread_handles.push(thread::spawn(move || {
-            let mut row: Vec<u8> = Vec::with_capacity(width);
-            for j in 0..width {
-                let nw = cloned_state[(i + height - 1) % height][(j + width - 1) % width];
-                let n = cloned_state[(i + height - 1) % height][j];
-                let ne = cloned_state[(i + height - 1) % height][(j + 1) % width];
-                let w = cloned_state[i][(j + width - 1) % width];
-                let c = cloned_state[i][j];
-                let e = cloned_state[i][(j + 1) % width];
-                let sw = cloned_state[(i + 1) % height][(j + width - 1) % width];
-                let s = cloned_state[(i + 1) % height][j];
-                let se = cloned_state[(i + 1) % height][(j + 1) % width];
-                let neighbor_cell_sum = nw + n + ne + w + e + sw + s + se;
-                if c == 0 && neighbor_cell_sum == 3 {
-                    row.push(1);
-                } else if c == 1 && (neighbor_cell_sum == 2 || neighbor_cell_sum == 3) {
-                    row.push(1);
-                } else {
-                    row.push(0);
+/// ルールを指定できる`game_of_life_in_parallel`
+///
+/// `LifeRule`はCopyなので、各threadにそのままmoveできる
+/// # Arguments
+/// * `state` - 現在の状態(Arc(参照のようなもの)に包まれている)
+/// * `rule` - B/S記法のルール
+pub fn game_of_life_in_parallel_with_rule(state: Arc<Grid>, rule: LifeRule) -> Grid {
+    WORKER_POOL.with(|pool| {
+        let mut pool = pool.borrow_mut();
+        // CPUの数だけworkerを作る
+        let pool = pool.get_or_insert_with(|| LifeWorkerPool::new(::rayon::current_num_threads()));
+        pool.step(state, rule)
+    })
+}
+
+/// 盤面を横長の帯に分けて、作りっぱなしのworker threadで計算する仕組み
+///
+/// 行ごとに毎世代threadを作ると、threadを作るコストの方が計算より大きくなってしまう。
+/// そこでworkerの数だけの帯に分け、各workerには`Arc<Grid>`と担当する行の範囲をchannelで送る。
+/// workerは計算した帯をchannelで送り返し、呼び出し側が次の状態のGridに並べる。
+/// 帯のbufferと(呼び出し側が手放した)古い状態のGridは次の世代で使い回す
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::game_of_life::{game_of_life_with_rule, LifeWorkerPool};
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_rule::LifeRule;
+/// use std::sync::Arc;
+///
+/// let rule = LifeRule::conway();
+/// let mut pool = LifeWorkerPool::new(3);
+/// // 行の数がworkerの数で割り切れなくても、workerより少なくてもよい
+/// for &(height, width) in [(10, 7), (2, 5), (1, 1)].iter() {
+///     let cells = (0..height * width).map(|i| (i * 7 % 11 % 3 % 2) as u8).collect();
+///     let mut expected = Grid::from_vec(height, width, cells).unwrap();
+///     let mut next_state = Grid::new(height, width);
+///     let mut state = Arc::new(expected.clone());
+///     for _ in 0..10 {
+///         state = Arc::new(pool.step(state, rule));
+///         game_of_life_with_rule(&mut expected, &mut next_state, rule, Boundary::Periodic);
+///         assert_eq!(*state, expected);
+///     }
+/// }
+/// ```
+pub struct LifeWorkerPool {
+    job_senders: Vec<Sender<Job>>,
+    result_receiver: Receiver<(usize, Vec<u8>)>,
+    handles: Vec<JoinHandle<()>>,
+    /// workerごとの帯のbuffer。計算中はworkerに貸している
+    band_buffers: Vec<Vec<u8>>,
+    /// 次の状態を書き込むのに使い回すGrid
+    spare_state: Option<Grid>,
+}
+
+/// workerに頼む1つの帯の計算
+struct Job {
+    state: Arc<Grid>,
+    rule: LifeRule,
+    rows: Range<usize>,
+    buffer: Vec<u8>,
+}
+
+impl LifeWorkerPool {
+    /// `workers`個のworker threadを作る
+    pub fn new(workers: usize) -> LifeWorkerPool {
+        let workers = workers.max(1);
+        let (result_sender, result_receiver) = channel();
+        let mut job_senders = vec![];
+        let mut handles = vec![];
+        for band in 0..workers {
+            let (job_sender, job_receiver) = channel::<Job>();
+            let result_sender = result_sender.clone();
+            // job_senderがdropされるとforが終わり、threadも終わる
+            handles.push(thread::spawn(move || {
+                for job in job_receiver {
+                    let Job {
+                        state,
+                        rule,
+                        rows,
+                        mut buffer,
+                    } = job;
+                    next_band(&state, rule, rows, &mut buffer);
+                    // 呼び出し側がArcからGridを取り出して使い回せるように、送り返す前にcloneしたArcを手放す
+                    drop(state);
+                    if result_sender.send((band, buffer)).is_err() {
+                        break;
+                    }
                 }
+            }));
+            job_senders.push(job_sender);
+        }
+        LifeWorkerPool {
+            job_senders: job_senders,
+            result_receiver: result_receiver,
+            handles: handles,
+            band_buffers: vec![vec![]; workers],
+            spare_state: None,
+        }
+    }
+
+    /// workerの数
+    pub fn workers(&self) -> usize {
+        self.job_senders.len()
+    }
+
+    /// 次の状態を計算する(周期境界)
+    ///
+    /// `state`を他に持っている人がいなければ、計算の後で中のGridを次の世代の書き込み先として取っておく
+    pub fn step(&mut self, state: Arc<Grid>, rule: LifeRule) -> Grid {
+        let (height, width) = state.dims();
+        let band_height = (height + self.workers() - 1) / self.workers();
+        let mut jobs = 0;
+        for (band, job_sender) in self.job_senders.iter().enumerate() {
+            let rows = (band * band_height).min(height)..((band + 1) * band_height).min(height);
+            if rows.start == rows.end {
+                break;
             }
-            // threadからの出力(i(高さ)は使っていないので実際には不要だが、待受側で順番が変わっていないか見るのに使える)
-            (i, row)
-        }));
+            let job = Job {
+                state: state.clone(),
+                rule: rule,
+                rows: rows,
+                buffer: mem::take(&mut self.band_buffers[band]),
+            };
+            job_sender.send(job).expect("a worker of LifeWorkerPool has stopped");
+            jobs += 1;
+        }
+
+        let mut next_state = self
+            .spare_state
+            .take()
+            .filter(|grid| grid.dims() == (height, width))
+            .unwrap_or_else(|| Grid::new(height, width));
+        for _ in 0..jobs {
+            let (band, buffer) = self
+                .result_receiver
+                .recv()
+                .expect("a worker of LifeWorkerPool has stopped");
+            let start = band * band_height * width;
+            next_state.cells_mut()[start..start + buffer.len()].copy_from_slice(&buffer);
+            self.band_buffers[band] = buffer;
+        }
+        // 全てのworkerがcloneしたArcを手放しているので、呼び出し側が他に持っていなければ取り出せる
+        self.spare_state = Arc::try_unwrap(state).ok();
+        next_state
+    }
+}
+
+impl Drop for LifeWorkerPool {
+    fn drop(&mut self) {
+        // channelを閉じて、全てのworkerが終わるのを待つ
+        self.job_senders.clear();
+        for handle in self.handles.drain(..) {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// `rows`の範囲の行の次の状態を`buffer`に書き込む
+fn next_band(state: &Grid, rule: LifeRule, rows: Range<usize>, buffer: &mut Vec<u8>) {
+    let width = state.width();
+    buffer.clear();
+    for i in rows {
+        for j in 0..width {
+            let neighbor_cell_sum = state.neighbors(i, j).sum::<u8>();
+            buffer.push(rule.next_state(state[(i, j)], neighbor_cell_sum));
+        }
     }
-    thread_handles
-        .into_iter()
-        .map(|h| h.join().unwrap().1) // threadの終了を待ち受ける。
-        .collect::<Vec<Vec<_>>>()
-
-    // ↓みたいに書いても良い
-    // let next_state: Vec<Vec<u8>> = Vec::with_capacity(height);
-    // for handle in thread_handles {
-    //     next_state.push(handle.join().unwrap().1);
-    // }
-    // next_state
 }
 
 /// ライフゲームのアルゴリズム
@@ -98,33 +263,58 @@ pub fn game_of_life_in_parallel(state: Arc<Vec<Vec<u8>>>, height: usize, width:
 /// * stateの参照を複数threadで共有できる(Arcに包む必要がない)
 /// # Arguments
 /// * `state` - 現在の状態への参照
-/// * `height` - セルの縦の数
-/// * `width` - セルの横の数
-pub fn game_of_life_by_rayon(state: &Vec<Vec<u8>>, height: usize, width: usize) -> Vec<Vec<u8>> {
+pub fn game_of_life_by_rayon(state: &Grid) -> Grid {
+    game_of_life_by_rayon_with_rule(state, LifeRule::conway())
+}
+
+/// ルールを指定できる`game_of_life_by_rayon`
+/// # Arguments
+/// * `state` - 現在の状態への参照
+/// * `rule` - B/S記法のルール
+///
+/// # Example
+/// 逐次版・thread版と同じ結果になる
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::game_of_life::*;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_rule::LifeRule;
+/// use std::sync::Arc;
+///
+/// let rule = "B36/S23".parse::<LifeRule>().unwrap();
+/// let cells = (0..12 * 10).map(|i| (i * 7 % 11 % 3 % 2) as u8).collect();
+/// let mut state = Grid::from_vec(12, 10, cells).unwrap();
+/// let mut next_state = Grid::new(12, 10);
+/// for _ in 0..20 {
+///     let by_rayon = game_of_life_by_rayon_with_rule(&state, rule);
+///     let in_parallel = game_of_life_in_parallel_with_rule(Arc::new(state.clone()), rule);
+///     game_of_life_with_rule(&mut state, &mut next_state, rule, Boundary::Periodic);
+///     assert_eq!(by_rayon, state);
+///     assert_eq!(in_parallel, state);
+/// }
+/// // 幅や高さが0の盤面はそのまま空の盤面になる
+/// assert_eq!(game_of_life_by_rayon_with_rule(&Grid::new(3, 0), rule), Grid::new(3, 0));
+/// assert_eq!(game_of_life_by_rayon_with_rule(&Grid::new(0, 3), rule), Grid::new(0, 3));
+/// ```
+pub fn game_of_life_by_rayon_with_rule(state: &Grid, rule: LifeRule) -> Grid {
     use rayon::prelude::*;
-    (0..height)
-        .into_par_iter() // 通常のinto_iter()をinto_par_iter()にするだけ
-        .map(|i| {
-            let mut row: Vec<u8> = Vec::with_capacity(width);
-            for j in 0..width {
-                let nw = state[(i + height - 1) % height][(j + width - 1) % width];
-                let n = state[(i + height - 1) % height][j];
-                let ne = state[(i + height - 1) % height][(j + 1) % width];
-                let w = state[i][(j + width - 1) % width];
-                let c = state[i][j];
-                let e = state[i][(j + 1) % width];
-                let sw = state[(i + 1) % height][(j + width - 1) % width];
-                let s = state[(i + 1) % height][j];
-                let se = state[(i + 1) % height][(j + 1) % width];
-                let neighbor_cell_sum = nw + n + ne + w + e + sw + s + se;
-                if c == 0 && neighbor_cell_sum == 3 {
-                    row.push(1);
-                } else if c == 1 && (neighbor_cell_sum == 2 || neighbor_cell_sum == 3) {
-                    row.push(1);
-                } else {
-                    row.push(0);
-                }
+    let (height, width) = state.dims();
+    let mut next_state = Grid::new(height, width);
+    // par_chunks_mut(0)はpanicするので、セルのない盤面は先に返す
+    if height == 0 || width == 0 {
+        return next_state;
+    }
+    next_state
+        .cells_mut()
+        .par_chunks_mut(width) // 通常のchunks_mut()をpar_chunks_mut()にするだけ。1行ずつ別々のthreadで計算する
+        .enumerate()
+        .for_each(|(i, row)| {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let neighbor_cell_sum = state.neighbors(i, j).sum::<u8>();
+                *cell = rule.next_state(state[(i, j)], neighbor_cell_sum);
             }
-            row
-        }).collect::<Vec<_>>()
+        });
+    next_state
 }
@@ -0,0 +1,282 @@
+use algorithm::boundary::Boundary;
+use algorithm::cellular_automata::cellular_automata;
+use failure;
+use ndarray::Array1;
+use num::{BigUint, Integer, ToPrimitive, Zero};
+use std::mem;
+
+/// 1次元セルラーオートマトンのルールが実装するtrait
+///
+/// `update`は周期境界条件で1フェーズ分計算するデフォルト実装を持つので、
+/// `next_state`だけ実装すればどんなルールでも`ArrayVisualizer::draw_loop_by_rule`で描画できる
+pub trait CellularAutomataRule {
+    /// 近傍半径
+    fn radius(&self) -> usize;
+
+    /// セルの状態数
+    fn states(&self) -> u32;
+
+    /// 近傍の状態から次の状態を求める
+    ///
+    /// # Arguments
+    /// * `neighborhood` - 左端から右端まで`2 * radius + 1`個のセルの状態
+    fn next_state(&self, neighborhood: &[u32]) -> u32;
+
+    /// フェーズ1回分の計算を行う
+    ///
+    /// `cellular_automata`と同じく周期境界条件で`state.1`に次の状態を計算し、`state.0`と入れ替える。
+    /// 空間の大きさは`state.0`の長さから決まる
+    fn update(&self, state: &mut (Array1<u32>, Array1<u32>)) {
+        let current_state = &mut state.0;
+        let next_state = &mut state.1;
+        let space_size = current_state.len();
+        let radius = self.radius();
+        let mut neighborhood = vec![0; 2 * radius + 1];
+        for i in 0..space_size {
+            for (j, cell) in neighborhood.iter_mut().enumerate() {
+                *cell = current_state[(i + space_size * radius + j - radius) % space_size];
+            }
+            next_state[i] = self.next_state(&neighborhood);
+        }
+        mem::swap(current_state, next_state);
+    }
+
+    /// 境界条件を指定してフェーズ1回分の計算を行う
+    ///
+    /// `Boundary::Open`の場合は、端から`radius`個以内に状態0以外のセルがあれば`state`の両方の配列を`radius`個広げる。
+    /// 返り値は左側に追加したセルの数で、元の座標系で観測したい場合に使う(`Open`以外では常に0)
+    ///
+    /// # Arguments
+    /// * `state` - 現在の状態と次の状態の組
+    /// * `boundary` - 境界条件
+    fn update_with_boundary(&self, state: &mut (Array1<u32>, Array1<u32>), boundary: Boundary) -> usize {
+        let radius = self.radius();
+        let added = if boundary == Boundary::Open {
+            grow_space(state, radius)
+        } else {
+            0
+        };
+        let current_state = &mut state.0;
+        let next_state = &mut state.1;
+        let space_size = current_state.len();
+        let mut neighborhood = vec![0; 2 * radius + 1];
+        for i in 0..space_size {
+            for (j, cell) in neighborhood.iter_mut().enumerate() {
+                *cell = match boundary.index((i + j) as isize - radius as isize, space_size) {
+                    Some(index) => current_state[index],
+                    None => boundary.outside_value(),
+                };
+            }
+            next_state[i] = self.next_state(&neighborhood);
+        }
+        mem::swap(current_state, next_state);
+        added
+    }
+}
+
+/// 端から`radius`個以内に状態0以外のセルがあれば、その側に`radius`個のセルを追加する。
+/// 左側に追加したセルの数を返す
+fn grow_space(state: &mut (Array1<u32>, Array1<u32>), radius: usize) -> usize {
+    let space_size = state.0.len();
+    let edge = radius.min(space_size);
+    let left_size = if state.0.iter().take(edge).any(|e| *e != 0) {
+        radius
+    } else {
+        0
+    };
+    let right_size = if state.0.iter().skip(space_size - edge).any(|e| *e != 0) {
+        radius
+    } else {
+        0
+    };
+    if left_size == 0 && right_size == 0 {
+        return 0;
+    }
+    let mut grown = Array1::<u32>::zeros(left_size + space_size + right_size);
+    grown.slice_mut(s![left_size..left_size + space_size]).assign(&state.0);
+    state.1 = Array1::<u32>::zeros(grown.len());
+    state.0 = grown;
+    left_size
+}
+
+/// `u8`はウルフラムのルールコーディングの数字として基本ルールを表す
+impl CellularAutomataRule for u8 {
+    fn radius(&self) -> usize {
+        1
+    }
+
+    fn states(&self) -> u32 {
+        2
+    }
+
+    fn next_state(&self, neighborhood: &[u32]) -> u32 {
+        let neighbor_cell_code = 4 * neighborhood[0] + 2 * neighborhood[1] + neighborhood[2];
+        ((self >> neighbor_cell_code) & 1) as u32
+    }
+
+    fn update(&self, state: &mut (Array1<u32>, Array1<u32>)) {
+        let space_size = state.0.len();
+        cellular_automata(state, *self, space_size);
+    }
+}
+
+/// 近傍半径・状態数を任意にした1次元セルラーオートマトンのルール表
+///
+/// セルの次の状態は、左右`radius`個ずつのセルと自分自身(合わせて`2 * radius + 1`個)の状態で決まる。
+/// 近傍の状態を左から順に並べて`states`進数とみなした値(近傍コード)をindexとして`table`を引く。
+/// ウルフラムのルール番号は`table`を`states`進数の各桁として並べたもの(`table[0]`が1の位)なので、
+/// 基本ルール(`radius = 1`, `states = 2`)のルール番号は`cellular_automata`のものと一致する。
+///
+/// # Example
+/// ```
+/// extern crate ndarray;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::generalized_cellular_automata::{CellularAutomataRule, RuleTable};
+/// use ndarray::Array1;
+///
+/// let rule = RuleTable::elementary(30);
+/// let mut state = (Array1::<u32>::zeros(8), Array1::<u32>::zeros(8));
+/// state.0[4] = 1;
+/// rule.update(&mut state);
+/// assert_eq!(state.0.to_vec(), vec![0, 0, 0, 1, 1, 1, 0, 0]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleTable {
+    radius: usize,
+    states: u32,
+    table: Vec<u32>,
+}
+
+impl RuleTable {
+    /// ルール表を直接与えてRuleTableを生成する
+    ///
+    /// # Arguments
+    /// * `radius` - 近傍半径(1以上)
+    /// * `states` - セルの状態数(2以上)
+    /// * `table` - 近傍コードごとの次の状態。長さは`states^(2 * radius + 1)`
+    pub fn new(radius: usize, states: u32, table: Vec<u32>) -> Result<RuleTable, failure::Error> {
+        let size = Self::table_size(radius, states)?;
+        if table.len() != size {
+            return Err(failure::err_msg(format!(
+                "rule table must have {} entries, but got {}",
+                size,
+                table.len()
+            )));
+        }
+        if let Some(invalid) = table.iter().find(|e| **e >= states) {
+            return Err(failure::err_msg(format!(
+                "rule table contains {} which is not a state of {}-state automaton",
+                invalid, states
+            )));
+        }
+        Ok(RuleTable {
+            radius: radius,
+            states: states,
+            table: table,
+        })
+    }
+
+    /// ウルフラムのルール番号からRuleTableを生成する
+    ///
+    /// ルール番号は`states^(states^(2 * radius + 1))`未満でなければならない。
+    /// u64に収まらない番号も扱えるように`BigUint`で受け取る
+    ///
+    /// # Arguments
+    /// * `radius` - 近傍半径(1以上)
+    /// * `states` - セルの状態数(2以上)
+    /// * `number` - ルール番号
+    pub fn from_number(radius: usize, states: u32, number: &BigUint) -> Result<RuleTable, failure::Error> {
+        let size = Self::table_size(radius, states)?;
+        let base = BigUint::from(states);
+        let mut rest = number.clone();
+        let mut table = Vec::with_capacity(size);
+        for _ in 0..size {
+            let (quotient, digit) = rest.div_rem(&base);
+            table.push(digit.to_u32().unwrap());
+            rest = quotient;
+        }
+        if !rest.is_zero() {
+            return Err(failure::err_msg(format!(
+                "rule number {} is too large for radius {} and {} states",
+                number, radius, states
+            )));
+        }
+        Self::new(radius, states, table)
+    }
+
+    /// 基本ルール(近傍半径1、2状態)のRuleTableを生成する
+    ///
+    /// # Arguments
+    /// * `rule` - ウルフラムのルールコーディングの数字
+    pub fn elementary(rule: u8) -> RuleTable {
+        let table = (0..8).map(|code| ((rule >> code) & 1) as u32).collect();
+        RuleTable {
+            radius: 1,
+            states: 2,
+            table: table,
+        }
+    }
+
+    /// 近傍コードごとの次の状態
+    pub fn table(&self) -> &[u32] {
+        &self.table
+    }
+
+    /// ウルフラムのルール番号
+    pub fn number(&self) -> BigUint {
+        let base = BigUint::from(self.states);
+        self.table
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, e| acc * &base + BigUint::from(*e))
+    }
+
+    fn table_size(radius: usize, states: u32) -> Result<usize, failure::Error> {
+        if radius < 1 {
+            return Err(failure::err_msg("radius must be 1 or more"));
+        }
+        if states < 2 {
+            return Err(failure::err_msg("states must be 2 or more"));
+        }
+        (states as usize).checked_pow(2 * radius as u32 + 1).ok_or_else(|| {
+            failure::err_msg(format!(
+                "rule table for radius {} and {} states is too large",
+                radius, states
+            ))
+        })
+    }
+}
+
+impl CellularAutomataRule for RuleTable {
+    fn radius(&self) -> usize {
+        self.radius
+    }
+
+    fn states(&self) -> u32 {
+        self.states
+    }
+
+    fn next_state(&self, neighborhood: &[u32]) -> u32 {
+        let neighbor_cell_code = neighborhood
+            .iter()
+            .fold(0usize, |code, cell| code * self.states as usize + *cell as usize);
+        self.table[neighbor_cell_code]
+    }
+
+    /// 近傍コードを直接計算するので、デフォルト実装より速い
+    fn update(&self, state: &mut (Array1<u32>, Array1<u32>)) {
+        let current_state = &mut state.0;
+        let next_state = &mut state.1;
+        let space_size = current_state.len();
+        for i in 0..space_size {
+            let mut neighbor_cell_code = 0usize;
+            for j in 0..(2 * self.radius + 1) {
+                let cell = current_state[(i + space_size * self.radius + j - self.radius) % space_size];
+                neighbor_cell_code = neighbor_cell_code * self.states as usize + cell as usize;
+            }
+            next_state[i] = self.table[neighbor_cell_code];
+        }
+        mem::swap(current_state, next_state);
+    }
+}
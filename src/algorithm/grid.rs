@@ -0,0 +1,199 @@
+use algorithm::boundary::Boundary;
+use failure;
+use ndarray::Array;
+use rand::{Rng, SeedableRng, StdRng};
+use std::ops::{Index, IndexMut};
+use std::slice::{Chunks, ChunksMut};
+use visualizer::matrix_visualizer::Matrix;
+
+/// ムーア近傍(周囲8セル)の相対座標。左上から右下の順
+const NEIGHBOR_OFFSETS: [(isize, isize); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// ライフゲームなどの2次元セルラーオートマトンの状態を持つ格子
+///
+/// セル`(i, j)`(`i`行`j`列)は`cells[i * width + j]`に入る。
+/// `Vec<Vec<u8>>`と違って全てのセルが1つの連続したメモリに並ぶので、行ごとの確保やflattenが要らない
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::grid::Grid;
+///
+/// let mut grid = Grid::new(3, 4);
+/// grid[(1, 0)] = 1;
+/// assert_eq!(grid.dims(), (3, 4));
+/// assert_eq!(grid.row(1), &[1, 0, 0, 0]);
+/// // 周期境界なので(1, 3)の右隣は(1, 0)
+/// assert_eq!(grid.neighbors(1, 3).sum::<u8>(), 1);
+/// assert_eq!(grid.neighbors(1, 2).sum::<u8>(), 0);
+/// assert_eq!(grid.to_matrix()[[1, 0]], 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid {
+    cells: Vec<u8>,
+    height: usize,
+    width: usize,
+}
+
+impl Grid {
+    /// 全てのセルが0のGridインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `height` - セルの縦の数
+    /// * `width` - セルの横の数
+    pub fn new(height: usize, width: usize) -> Grid {
+        Grid {
+            cells: vec![0; height * width],
+            height: height,
+            width: width,
+        }
+    }
+
+    /// 行ごとに並べたセルからGridインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `height` - セルの縦の数
+    /// * `width` - セルの横の数
+    /// * `cells` - `height * width`個のセル
+    pub fn from_vec(height: usize, width: usize, cells: Vec<u8>) -> Result<Grid, failure::Error> {
+        if cells.len() != height * width {
+            return Err(failure::err_msg(format!(
+                "{} cells do not fit in a {}x{} grid",
+                cells.len(),
+                height,
+                width
+            )));
+        }
+        Ok(Grid {
+            cells: cells,
+            height: height,
+            width: width,
+        })
+    }
+
+    /// 各セルが確率`density`で1になるランダムなGridインスタンスを生成する
+    ///
+    /// 同じ`seed`なら同じ状態になる
+    ///
+    /// # Arguments
+    /// * `height` - セルの縦の数
+    /// * `width` - セルの横の数
+    /// * `density` - 1になる確率
+    /// * `seed` - 乱数のseed
+    ///
+    /// # Example
+    /// ```
+    /// extern crate my_alife;
+    ///
+    /// use my_alife::algorithm::grid::Grid;
+    ///
+    /// let grid = Grid::random(30, 80, 0.25, 7);
+    /// assert_eq!(grid.dims(), (30, 80));
+    /// assert_eq!(grid, Grid::random(30, 80, 0.25, 7));
+    /// assert!(grid.population() > 400 && grid.population() < 800);
+    /// // 縦30行、横80列のMatrixになる
+    /// assert_eq!(grid.to_matrix().shape(), &[30, 80]);
+    /// ```
+    pub fn random(height: usize, width: usize, density: f64, seed: usize) -> Grid {
+        let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+        let mut grid = Grid::new(height, width);
+        for cell in &mut grid.cells {
+            *cell = if rng.gen::<f64>() < density { 1 } else { 0 };
+        }
+        grid
+    }
+
+    /// セルの縦の数
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// セルの横の数
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// `(height, width)`
+    pub fn dims(&self) -> (usize, usize) {
+        (self.height, self.width)
+    }
+
+    /// 全てのセル(行ごとに並んでいる)
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// 全てのセルのmutableな参照
+    pub fn cells_mut(&mut self) -> &mut [u8] {
+        &mut self.cells
+    }
+
+    /// `i`行目のセル
+    pub fn row(&self, i: usize) -> &[u8] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    /// 上の行から順に1行ずつ取り出すiterator
+    pub fn rows(&self) -> Chunks<'_, u8> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// 上の行から順に1行ずつmutableな参照を取り出すiterator
+    pub fn rows_mut(&mut self) -> ChunksMut<'_, u8> {
+        self.cells.chunks_mut(self.width.max(1))
+    }
+
+    /// 周期境界でのセル`(i, j)`のムーア近傍(周囲8セル)の状態
+    pub fn neighbors<'a>(&'a self, i: usize, j: usize) -> impl Iterator<Item = u8> + 'a {
+        self.neighbors_with_boundary(i, j, Boundary::Periodic)
+    }
+
+    /// 境界条件を指定したセル`(i, j)`のムーア近傍(周囲8セル)の状態
+    ///
+    /// 範囲外の座標は`boundary`で写し、写せない場合は`boundary.outside_value()`になる
+    pub fn neighbors_with_boundary<'a>(
+        &'a self,
+        i: usize,
+        j: usize,
+        boundary: Boundary,
+    ) -> impl Iterator<Item = u8> + 'a {
+        NEIGHBOR_OFFSETS.iter().map(move |&(di, dj)| {
+            match (
+                boundary.index(i as isize + di, self.height),
+                boundary.index(j as isize + dj, self.width),
+            ) {
+                (Some(y), Some(x)) => self.cells[y * self.width + x],
+                _ => boundary.outside_value() as u8,
+            }
+        })
+    }
+
+    /// 状態1以上のセルの数
+    pub fn population(&self) -> usize {
+        self.cells.iter().filter(|cell| **cell != 0).count()
+    }
+
+    /// `MatrixVisualizer`で描画できる`Matrix<f32>`に変換する(`height`行`width`列)
+    pub fn to_matrix(&self) -> Matrix<f32> {
+        Array::from_shape_vec(
+            (self.height, self.width),
+            self.cells.iter().map(|cell| *cell as f32).collect(),
+        )
+        .unwrap()
+    }
+}
+
+impl Index<(usize, usize)> for Grid {
+    type Output = u8;
+
+    fn index(&self, (i, j): (usize, usize)) -> &u8 {
+        &self.cells[i * self.width + j]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Grid {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut u8 {
+        &mut self.cells[i * self.width + j]
+    }
+}
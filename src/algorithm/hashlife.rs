@@ -0,0 +1,510 @@
+use algorithm::grid::Grid;
+use algorithm::life_pattern::Pattern;
+use algorithm::life_rule::LifeRule;
+use failure;
+use std::collections::HashMap;
+
+/// ノードの番号(`HashLife::nodes`の添字)
+type NodeId = usize;
+
+/// 死んだセル(レベル0の葉)
+const DEAD: NodeId = 0;
+/// 生きたセル(レベル0の葉)
+const ALIVE: NodeId = 1;
+
+/// 根のレベルの最小値(8x8)
+const MIN_LEVEL: u8 = 3;
+/// 根のレベルの最大値。座標(`-2^61`から`2^61 - 1`)が`i64`に収まるようにする
+const MAX_LEVEL: u8 = 62;
+
+/// キャッシュするノード数の既定値
+const DEFAULT_CACHE_SIZE: usize = 1 << 22;
+
+/// 4分木のノード。レベル`k`のノードは`2^k`x`2^k`のセルを表し、4つの子はレベル`k-1`
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    level: u8,
+    /// 子ノード(北西、北東、南西、南東の順)。葉では使わない
+    children: [NodeId; 4],
+    population: u64,
+}
+
+/// HashLifeアルゴリズムでライフゲームを計算する構造体
+///
+/// 盤面を4分木で表し、同じ内容のノードは1つだけ作る(hash-consing)。
+/// レベル`k`のノードについて「`2^(k-2)`世代後の中央`2^(k-1)`x`2^(k-1)`の状態」をメモ化しておくことで、
+/// 同じ模様が繰り返し現れるパターンでは世代数の対数に近い時間で計算できる。
+///
+/// 盤面は無限に広く、根のノードは原点を中心に必要なだけ広がる。座標`(i, j)`は`i`行`j`列で、負の値も使える
+///
+/// # Example
+/// グライダーは4世代で斜めに1セル進むので、`2^10`世代後には256セル進んでいる
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::hashlife::HashLife;
+/// use my_alife::algorithm::life_pattern::Pattern;
+///
+/// let glider = Pattern::from_rle("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+/// let mut life = HashLife::from_pattern(&glider, 0, 0).unwrap();
+/// life.step_pow2(10);
+/// assert_eq!(life.generation(), 1024);
+/// assert_eq!(life.population(), 5);
+/// assert_eq!(life.bounding_box(), Some((256, 256, 3, 3)));
+/// assert_eq!(&life.to_grid(256, 256, 3, 3), glider.grid());
+///
+/// // 10億世代でも一瞬で計算できる
+/// life.step(1_000_000_000);
+/// assert_eq!(life.generation(), 1_000_001_024);
+/// assert_eq!(life.population(), 5);
+/// ```
+pub struct HashLife {
+    rule: LifeRule,
+    nodes: Vec<Node>,
+    /// 4つの子からノードを引く表(hash-consing)
+    index: HashMap<[NodeId; 4], NodeId>,
+    /// `(ノード, log2(世代数))`から計算結果のノードを引く表
+    memo: HashMap<(NodeId, u8), NodeId>,
+    /// レベルごとの全てのセルが死んでいるノード
+    empty: Vec<NodeId>,
+    root: NodeId,
+    generation: u64,
+    cache_size: usize,
+}
+
+impl HashLife {
+    /// 全てのセルが死んでいるHashLifeインスタンスを生成する
+    ///
+    /// 空白から誕生する(B0を含む)ルールは、無限に広い盤面の全てが毎世代変化してしまうので使えない
+    ///
+    /// # Arguments
+    /// * `rule` - B/S記法のルール
+    pub fn new(rule: LifeRule) -> Result<HashLife, failure::Error> {
+        Self::with_cache_size(rule, DEFAULT_CACHE_SIZE)
+    }
+
+    /// キャッシュするノード数の上限を指定してHashLifeインスタンスを生成する
+    ///
+    /// `step`や`step_pow2`の後にノード数が`cache_size`を超えていたら、
+    /// 今の盤面から辿れないノードとメモ化した計算結果を捨てる
+    ///
+    /// # Arguments
+    /// * `rule` - B/S記法のルール
+    /// * `cache_size` - キャッシュするノード数の上限
+    pub fn with_cache_size(rule: LifeRule, cache_size: usize) -> Result<HashLife, failure::Error> {
+        if rule.is_born(0) {
+            return Err(failure::err_msg(format!(
+                "HashLife does not support B0 rules ({})",
+                rule
+            )));
+        }
+        let mut life = HashLife {
+            rule: rule,
+            nodes: vec![],
+            index: HashMap::new(),
+            memo: HashMap::new(),
+            empty: vec![],
+            root: DEAD,
+            generation: 0,
+            cache_size: cache_size,
+        };
+        life.reset_nodes();
+        life.root = life.empty(MIN_LEVEL);
+        Ok(life)
+    }
+
+    /// Gridの左上のセルが座標`(top, left)`に来るように置いたHashLifeインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `grid` - 初期状態
+    /// * `rule` - B/S記法のルール
+    /// * `top` - `grid`の上端の行
+    /// * `left` - `grid`の左端の列
+    pub fn from_grid(grid: &Grid, rule: LifeRule, top: i64, left: i64) -> Result<HashLife, failure::Error> {
+        let mut life = HashLife::new(rule)?;
+        for i in 0..grid.height() {
+            for j in 0..grid.width() {
+                if grid[(i, j)] != 0 {
+                    life.set(top + i as i64, left + j as i64, true);
+                }
+            }
+        }
+        Ok(life)
+    }
+
+    /// パターンの左上が座標`(top, left)`に来るように置いたHashLifeインスタンスを生成する
+    ///
+    /// パターンにルールが書かれていればそのルール、なければB3/S23を使う
+    pub fn from_pattern(pattern: &Pattern, top: i64, left: i64) -> Result<HashLife, failure::Error> {
+        let rule = pattern.rule().unwrap_or_else(LifeRule::conway);
+        Self::from_grid(pattern.grid(), rule, top, left)
+    }
+
+    /// ルール
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// 今の世代
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 生きているセルの数
+    pub fn population(&self) -> u64 {
+        self.nodes[self.root].population
+    }
+
+    /// 今持っているノードの数
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// セル`(i, j)`の状態(0が死、1が生)
+    pub fn get(&self, i: i64, j: i64) -> u8 {
+        let level = self.nodes[self.root].level;
+        let half = 1i64 << (level - 1);
+        if i < -half || i >= half || j < -half || j >= half {
+            return 0;
+        }
+        let mut node = self.root;
+        let (mut y, mut x) = ((i + half) as u64, (j + half) as u64);
+        for level in (1..level + 1).rev() {
+            let half = 1u64 << (level - 1);
+            let quadrant = ((y >= half) as usize) * 2 + (x >= half) as usize;
+            node = self.nodes[node].children[quadrant];
+            y %= half;
+            x %= half;
+        }
+        (node == ALIVE) as u8
+    }
+
+    /// セル`(i, j)`の状態を変える。盤面の外なら根を広げる
+    ///
+    /// `i`と`j`は`-2^61`以上`2^61`未満でなければならない
+    pub fn set(&mut self, i: i64, j: i64, alive: bool) {
+        loop {
+            let half = 1i64 << (self.nodes[self.root].level - 1);
+            if -half <= i && i < half && -half <= j && j < half {
+                break;
+            }
+            self.expand_root();
+        }
+        let half = 1i64 << (self.nodes[self.root].level - 1);
+        let root = self.root;
+        self.root = self.set_cell(root, (i + half) as u64, (j + half) as u64, alive);
+    }
+
+    /// `2^log2`世代進める
+    ///
+    /// 座標が`i64`に収まるように、`log2`は59以下でなければならない。
+    /// 生きたセルが広がって根のレベルが上限(62)を超えるときはpanicする
+    pub fn step_pow2(&mut self, log2: u8) {
+        assert!(log2 <= MAX_LEVEL - 3, "log2 must be {} or less", MAX_LEVEL - 3);
+        // 2^log2世代の間に生きたセルが広がっても、計算結果の中央の範囲からはみ出さない大きさまで広げる
+        while self.nodes[self.root].level < log2 + 3 || !self.is_padded(self.root) {
+            self.expand_root();
+        }
+        let root = self.root;
+        self.root = self.successor(root, log2);
+        self.generation += 1 << log2;
+        if self.nodes.len() > self.cache_size {
+            self.collect_garbage();
+        }
+    }
+
+    /// `generations`世代進める。2進数で表したときの各bitごとに`step_pow2`を呼ぶ(`2^60`未満)
+    ///
+    /// # Example
+    /// `game_of_life`で1世代ずつ計算した結果と同じになる
+    /// ```
+    /// extern crate my_alife;
+    ///
+    /// use my_alife::algorithm::boundary::Boundary;
+    /// use my_alife::algorithm::game_of_life::game_of_life_with_boundary;
+    /// use my_alife::algorithm::grid::Grid;
+    /// use my_alife::algorithm::hashlife::HashLife;
+    /// use my_alife::algorithm::life_rule::LifeRule;
+    ///
+    /// // Rペントミノ
+    /// let mut state = Grid::new(64, 64);
+    /// for &(i, j) in [(30, 31), (30, 32), (31, 30), (31, 31), (32, 31)].iter() {
+    ///     state[(i, j)] = 1;
+    /// }
+    /// let mut life = HashLife::from_grid(&state, LifeRule::conway(), 0, 0).unwrap();
+    /// let mut next_state = Grid::new(64, 64);
+    /// for _ in 0..5 {
+    ///     for _ in 0..3 {
+    ///         game_of_life_with_boundary(&mut state, &mut next_state, Boundary::Fixed(0));
+    ///     }
+    ///     life.step(3);
+    ///     assert_eq!(life.to_grid(0, 0, 64, 64), state);
+    /// }
+    /// ```
+    pub fn step(&mut self, generations: u64) {
+        assert!(
+            generations >> (MAX_LEVEL - 2) == 0,
+            "generations must be less than 2^{}",
+            MAX_LEVEL - 2
+        );
+        for log2 in 0..MAX_LEVEL - 2 {
+            if (generations >> log2) & 1 == 1 {
+                self.step_pow2(log2);
+            }
+        }
+    }
+
+    /// 生きているセルを全て含む最小の長方形`(top, left, height, width)`。生きているセルがなければ`None`
+    pub fn bounding_box(&self) -> Option<(i64, i64, usize, usize)> {
+        if self.population() == 0 {
+            return None;
+        }
+        let level = self.nodes[self.root].level;
+        let half = 1i64 << (level - 1);
+        let mut bounds = (i64::MAX, i64::MAX, i64::MIN, i64::MIN);
+        self.extend_bounds(self.root, -half, -half, &mut bounds);
+        let (top, left, bottom, right) = bounds;
+        Some((top, left, (bottom - top + 1) as usize, (right - left + 1) as usize))
+    }
+
+    /// 左上が`(top, left)`の`height`x`width`の範囲をGridに書き出す
+    pub fn to_grid(&self, top: i64, left: i64, height: usize, width: usize) -> Grid {
+        let mut grid = Grid::new(height, width);
+        for i in 0..height {
+            for j in 0..width {
+                grid[(i, j)] = self.get(top + i as i64, left + j as i64);
+            }
+        }
+        grid
+    }
+
+    /// 今の盤面から辿れないノードとメモ化した計算結果を捨てる
+    pub fn collect_garbage(&mut self) {
+        let old_nodes = ::std::mem::take(&mut self.nodes);
+        self.reset_nodes();
+        let root = self.root;
+        self.root = self.copy_node(&old_nodes, root, &mut HashMap::new());
+    }
+
+    fn copy_node(&mut self, old_nodes: &[Node], id: NodeId, copied: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if id == DEAD || id == ALIVE {
+            return id;
+        }
+        if let Some(new_id) = copied.get(&id) {
+            return *new_id;
+        }
+        let children = old_nodes[id].children;
+        let nw = self.copy_node(old_nodes, children[0], copied);
+        let ne = self.copy_node(old_nodes, children[1], copied);
+        let sw = self.copy_node(old_nodes, children[2], copied);
+        let se = self.copy_node(old_nodes, children[3], copied);
+        let new_id = self.join(nw, ne, sw, se);
+        copied.insert(id, new_id);
+        new_id
+    }
+
+    fn reset_nodes(&mut self) {
+        self.nodes = vec![
+            Node {
+                level: 0,
+                children: [DEAD; 4],
+                population: 0,
+            },
+            Node {
+                level: 0,
+                children: [DEAD; 4],
+                population: 1,
+            },
+        ];
+        self.index.clear();
+        self.memo.clear();
+        self.empty = vec![DEAD];
+    }
+
+    /// 4つの子からノードを作る。同じ子を持つノードが既にあればそれを返す
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(id) = self.index.get(&children) {
+            return *id;
+        }
+        let node = Node {
+            level: self.nodes[nw].level + 1,
+            children: children,
+            population: children.iter().map(|child| self.nodes[*child].population).sum(),
+        };
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        self.index.insert(children, id);
+        id
+    }
+
+    /// レベル`level`の全てのセルが死んでいるノード
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let child = *self.empty.last().unwrap();
+            let id = self.join(child, child, child, child);
+            self.empty.push(id);
+        }
+        self.empty[level as usize]
+    }
+
+    /// 根を1つ上のレベルに広げる。座標が`i64`に収まらなくなるレベルには広げない
+    fn expand_root(&mut self) {
+        let level = self.nodes[self.root].level;
+        assert!(
+            level < MAX_LEVEL,
+            "the root of HashLife cannot grow beyond level {} (coordinates would overflow i64)",
+            MAX_LEVEL
+        );
+        let root = self.root;
+        self.root = self.expand(root);
+    }
+
+    /// 中心を変えずに1つ上のレベルに広げる
+    fn expand(&mut self, id: NodeId) -> NodeId {
+        let node = self.nodes[id];
+        let e = self.empty(node.level - 1);
+        let [nw, ne, sw, se] = node.children;
+        let nw = self.join(e, e, e, nw);
+        let ne = self.join(e, e, ne, e);
+        let sw = self.join(e, sw, e, e);
+        let se = self.join(se, e, e, e);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// 中央の1つ下のレベルのノード
+    fn centre(&mut self, id: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[id].children;
+        let nw = self.nodes[nw].children[3];
+        let ne = self.nodes[ne].children[2];
+        let sw = self.nodes[sw].children[1];
+        let se = self.nodes[se].children[0];
+        self.join(nw, ne, sw, se)
+    }
+
+    /// 生きたセルが全て中央の1/4(縦横それぞれ中央の1/4)に収まっているか
+    fn is_padded(&mut self, id: NodeId) -> bool {
+        let centre = self.centre(id);
+        let centre = self.centre(centre);
+        self.nodes[centre].population == self.nodes[id].population
+    }
+
+    /// `(y, x)`(ノードの左上からの座標)のセルを変えたノード
+    fn set_cell(&mut self, id: NodeId, y: u64, x: u64, alive: bool) -> NodeId {
+        let level = self.nodes[id].level;
+        if level == 0 {
+            return if alive { ALIVE } else { DEAD };
+        }
+        let half = 1u64 << (level - 1);
+        let mut children = self.nodes[id].children;
+        let quadrant = ((y >= half) as usize) * 2 + (x >= half) as usize;
+        children[quadrant] = self.set_cell(children[quadrant], y % half, x % half, alive);
+        self.join(children[0], children[1], children[2], children[3])
+    }
+
+    fn extend_bounds(&self, id: NodeId, top: i64, left: i64, bounds: &mut (i64, i64, i64, i64)) {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return;
+        }
+        if node.level == 0 {
+            bounds.0 = bounds.0.min(top);
+            bounds.1 = bounds.1.min(left);
+            bounds.2 = bounds.2.max(top);
+            bounds.3 = bounds.3.max(left);
+            return;
+        }
+        let half = 1i64 << (node.level - 1);
+        self.extend_bounds(node.children[0], top, left, bounds);
+        self.extend_bounds(node.children[1], top, left + half, bounds);
+        self.extend_bounds(node.children[2], top + half, left, bounds);
+        self.extend_bounds(node.children[3], top + half, left + half, bounds);
+    }
+
+    /// レベル`k`のノードの中央`2^(k-1)`x`2^(k-1)`を`2^log2`世代進めたノード(`log2 <= k - 2`)
+    fn successor(&mut self, id: NodeId, log2: u8) -> NodeId {
+        let node = self.nodes[id];
+        if node.population == 0 {
+            return self.empty(node.level - 1);
+        }
+        if let Some(result) = self.memo.get(&(id, log2)) {
+            return *result;
+        }
+        let result = if node.level == 2 {
+            self.successor_of_level2(id)
+        } else {
+            let [nw, ne, sw, se] = node.children;
+            let [_, nw_ne, nw_sw, nw_se] = self.nodes[nw].children;
+            let [ne_nw, _, ne_sw, ne_se] = self.nodes[ne].children;
+            let [sw_nw, sw_ne, _, sw_se] = self.nodes[sw].children;
+            let [se_nw, se_ne, se_sw, _] = self.nodes[se].children;
+            // 3x3に並んだ、半分ずつ重なる9つのレベルk-1のノード
+            let n00 = nw;
+            let n01 = self.join(nw_ne, ne_nw, nw_se, ne_sw);
+            let n02 = ne;
+            let n10 = self.join(nw_sw, nw_se, sw_nw, sw_ne);
+            let n11 = self.join(nw_se, ne_sw, sw_ne, se_nw);
+            let n12 = self.join(ne_sw, ne_se, se_nw, se_ne);
+            let n20 = sw;
+            let n21 = self.join(sw_ne, se_nw, sw_se, se_sw);
+            let n22 = se;
+            // 最大の速さ(2^(k-2)世代)なら1段目でも2^(k-3)世代進め、そうでなければ1段目は中央を切り出すだけにする
+            let full_speed = log2 + 2 == node.level;
+            let stage = |life: &mut HashLife, id: NodeId| {
+                if full_speed {
+                    life.successor(id, log2 - 1)
+                } else {
+                    life.centre(id)
+                }
+            };
+            let r00 = stage(self, n00);
+            let r01 = stage(self, n01);
+            let r02 = stage(self, n02);
+            let r10 = stage(self, n10);
+            let r11 = stage(self, n11);
+            let r12 = stage(self, n12);
+            let r20 = stage(self, n20);
+            let r21 = stage(self, n21);
+            let r22 = stage(self, n22);
+            let second_log2 = if full_speed { log2 - 1 } else { log2 };
+            let nw = self.join(r00, r01, r10, r11);
+            let ne = self.join(r01, r02, r11, r12);
+            let sw = self.join(r10, r11, r20, r21);
+            let se = self.join(r11, r12, r21, r22);
+            let nw = self.successor(nw, second_log2);
+            let ne = self.successor(ne, second_log2);
+            let sw = self.successor(sw, second_log2);
+            let se = self.successor(se, second_log2);
+            self.join(nw, ne, sw, se)
+        };
+        self.memo.insert((id, log2), result);
+        result
+    }
+
+    /// レベル2(4x4)のノードの中央2x2を1世代進めたノード
+    fn successor_of_level2(&mut self, id: NodeId) -> NodeId {
+        let mut cells = [[0u8; 4]; 4];
+        let children = self.nodes[id].children;
+        for (quadrant, child) in children.iter().enumerate() {
+            for (k, leaf) in self.nodes[*child].children.iter().enumerate() {
+                let i = (quadrant / 2) * 2 + k / 2;
+                let j = (quadrant % 2) * 2 + k % 2;
+                cells[i][j] = (*leaf == ALIVE) as u8;
+            }
+        }
+        let mut next = [DEAD; 4];
+        for (k, next_cell) in next.iter_mut().enumerate() {
+            let (i, j) = (1 + k / 2, 1 + k % 2);
+            // 周囲3x3の和から中心を引く
+            let block_sum = cells[i - 1..i + 2]
+                .iter()
+                .flat_map(|row| row[j - 1..j + 2].iter())
+                .sum::<u8>();
+            let neighbor_cell_sum = block_sum - cells[i][j];
+            if self.rule.next_state(cells[i][j], neighbor_cell_sum) == 1 {
+                *next_cell = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+}
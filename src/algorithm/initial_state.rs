@@ -0,0 +1,100 @@
+use failure;
+use ndarray::Array1;
+use rand::{Rng, SeedableRng, StdRng};
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+/// 中央のセルだけが1で、残りが0の初期状態
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::initial_state::single_seed;
+///
+/// assert_eq!(single_seed(5).to_vec(), vec![0, 0, 1, 0, 0]);
+/// ```
+pub fn single_seed(len: usize) -> Array1<u32> {
+    let mut state = Array1::<u32>::zeros(len);
+    if len > 0 {
+        state[len / 2] = 1;
+    }
+    state
+}
+
+/// 各セルが確率`density`で1になるランダムな初期状態
+///
+/// 同じ`seed`なら同じ初期状態になる
+///
+/// # Arguments
+/// * `len` - セルの数
+/// * `density` - 1になる確率
+/// * `seed` - 乱数のseed
+pub fn random(len: usize, density: f64, seed: usize) -> Array1<u32> {
+    let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+    Array1::from_vec(
+        (0..len)
+            .map(|_| if rng.gen::<f64>() < density { 1 } else { 0 })
+            .collect(),
+    )
+}
+
+/// 各セルが`0..states`から一様に選ばれるランダムな初期状態
+///
+/// 同じ`seed`なら同じ初期状態になる。`states`が0のときはpanicする
+///
+/// # Arguments
+/// * `len` - セルの数
+/// * `states` - セルの状態数(1以上)
+/// * `seed` - 乱数のseed
+pub fn random_states(len: usize, states: u32, seed: usize) -> Array1<u32> {
+    assert!(states > 0, "random states need at least 1 state");
+    let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+    Array1::from_vec((0..len).map(|_| rng.gen_range(0, states)).collect())
+}
+
+/// `motif`を先頭から繰り返し並べた初期状態
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::initial_state::repeat_motif;
+///
+/// assert_eq!(repeat_motif(7, &[1, 1, 0]).to_vec(), vec![1, 1, 0, 1, 1, 0, 1]);
+/// ```
+pub fn repeat_motif(len: usize, motif: &[u32]) -> Array1<u32> {
+    assert!(!motif.is_empty(), "motif must not be empty");
+    Array1::from_vec(motif.iter().cloned().cycle().take(len).collect())
+}
+
+/// `"0110..."`のような数字の並びから初期状態を作る。空白と改行は無視する
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::initial_state::parse;
+///
+/// assert_eq!(parse("0110 2").unwrap().to_vec(), vec![0, 1, 1, 0, 2]);
+/// assert!(parse("01x0").is_err());
+/// ```
+pub fn parse(s: &str) -> Result<Array1<u32>, failure::Error> {
+    let cells = s
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            c.to_digit(10)
+                .ok_or_else(|| failure::err_msg(format!("invalid cell '{}' in initial state", c)))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Array1::from_vec(cells))
+}
+
+/// `parse`と同じ形式で書かれたテキストファイルから初期状態を読み込む
+pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Array1<u32>, failure::Error> {
+    let mut contents = String::new();
+    File::open(path)?.read_to_string(&mut contents)?;
+    parse(&contents)
+}
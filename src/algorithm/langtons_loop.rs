@@ -0,0 +1,234 @@
+use algorithm::boundary::Boundary;
+use algorithm::grid::Grid;
+use failure;
+use std::fs::File;
+use std::io::prelude::*;
+use std::mem;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Langtonのループの初期状態。空白は状態0
+const INITIAL_LOOP: &str = " 22222222
+2170140142
+2022222202
+272    212
+212    212
+202    212
+272    212
+21222222122222
+207107107111112
+ 2222222222222";
+
+/// フォン・ノイマン近傍(上下左右の4セル)を使うセルラーオートマトンの状態遷移表
+///
+/// 1行に1つの遷移を`CNESWC'`の6桁(中心、北、東、南、西の状態と、中心の次の状態)で書く。
+/// 近傍を90度ずつ回転させた遷移(`NESW`、`ESWN`、`SWNE`、`WNES`)は全て同じ次の状態になる。
+/// `#`で始まる行と空行は無視する。状態数は表に出てくる最大の状態に1を足したもの
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::langtons_loop::RuleTable;
+///
+/// let table = "# comment\n012345\n100002".parse::<RuleTable>().unwrap();
+/// assert_eq!(table.states(), 6);
+/// assert_eq!(table.next_state(0, [1, 2, 3, 4]), Some(5));
+/// // 回転させた近傍でも同じ
+/// assert_eq!(table.next_state(0, [3, 4, 1, 2]), Some(5));
+/// // 裏返した近傍は別の遷移
+/// assert_eq!(table.next_state(0, [1, 4, 3, 2]), None);
+/// assert!("01234".parse::<RuleTable>().is_err());
+/// assert!("012345\n012346".parse::<RuleTable>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleTable {
+    states: u8,
+    transitions: Vec<Option<u8>>,
+}
+
+impl RuleTable {
+    /// ファイルから読み込む
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<RuleTable, failure::Error> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+        contents.parse::<RuleTable>()
+    }
+
+    /// 状態数
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// 次の状態。表に遷移がなければ`None`
+    ///
+    /// # Arguments
+    /// * `center` - 中心のセルの状態
+    /// * `neighbors` - 北、東、南、西のセルの状態
+    pub fn next_state(&self, center: u8, neighbors: [u8; 4]) -> Option<u8> {
+        if center >= self.states || neighbors.iter().any(|&cell| cell >= self.states) {
+            return None;
+        }
+        self.transitions[transition_index(self.states, center, neighbors)]
+    }
+}
+
+impl FromStr for RuleTable {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<RuleTable, failure::Error> {
+        let mut entries = Vec::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if line.starts_with('#') || line.is_empty() {
+                continue;
+            }
+            let digits = line
+                .chars()
+                .filter_map(|c| c.to_digit(10))
+                .map(|d| d as u8)
+                .collect::<Vec<_>>();
+            if digits.len() != 6 || line.chars().count() != 6 {
+                return Err(failure::err_msg(format!(
+                    "\"{}\" is not a transition of 6 digits (CNESWC')",
+                    line
+                )));
+            }
+            entries.push(digits);
+        }
+        let states = entries
+            .iter()
+            .flat_map(|digits| digits.iter())
+            .max()
+            .map_or(0, |max| max + 1);
+
+        let mut transitions = vec![None; (states as usize).pow(5)];
+        for digits in &entries {
+            let mut neighbors = [digits[1], digits[2], digits[3], digits[4]];
+            for _ in 0..4 {
+                let transition = &mut transitions[transition_index(states, digits[0], neighbors)];
+                match *transition {
+                    Some(next) if next != digits[5] => {
+                        return Err(failure::err_msg(format!(
+                            "transition {}{}{}{}{} goes to both {} and {}",
+                            digits[0], neighbors[0], neighbors[1], neighbors[2], neighbors[3], next, digits[5]
+                        )));
+                    }
+                    _ => *transition = Some(digits[5]),
+                }
+                neighbors.rotate_left(1);
+            }
+        }
+        Ok(RuleTable {
+            states: states,
+            transitions: transitions,
+        })
+    }
+}
+
+/// Langtonのループの初期状態(10行15列)
+///
+/// 状態2の鞘(さや)の中を、遺伝情報を表す状態7(前進)と状態4(左折)の信号が状態1の芯の上を回っている
+pub fn initial_loop() -> Grid {
+    parse_states(INITIAL_LOOP).unwrap()
+}
+
+/// 数字で描いた状態をGridにする
+///
+/// 各文字がセルの状態で、空白は0。短い行の右側は0とみなす
+pub fn parse_states(s: &str) -> Result<Grid, failure::Error> {
+    let rows = s.lines().collect::<Vec<_>>();
+    let height = rows.len();
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let mut grid = Grid::new(height, width);
+    for (i, row) in rows.iter().enumerate() {
+        for (j, c) in row.chars().enumerate() {
+            grid[(i, j)] = match c {
+                ' ' => 0,
+                _ => c
+                    .to_digit(10)
+                    .ok_or_else(|| failure::err_msg(format!("invalid state '{}'", c)))? as u8,
+            };
+        }
+    }
+    Ok(grid)
+}
+
+/// 状態遷移表を使ったフォン・ノイマン近傍の2次元セルラーオートマトン
+///
+/// 現在のstateを元に次の瞬間のstate(next_state)を計算しstateとnext_stateを入れ替える。
+/// 表に遷移がないセルは状態を変えない
+///
+/// # Arguments
+/// * `state` - 現在の状態
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+/// * `table` - 状態遷移表
+/// * `boundary` - 境界条件
+///
+/// # Example
+/// Langtonのループは151ステップで右隣に自分と同じループを作る
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::langtons_loop::{initial_loop, langtons_loop, RuleTable};
+///
+/// let table = RuleTable::load("res/rules/langtons_loop.table").unwrap();
+/// assert_eq!(table.states(), 8);
+/// let parent = initial_loop();
+/// let (height, width) = parent.dims();
+/// let mut state = Grid::new(30, 40);
+/// let mut next_state = Grid::new(30, 40);
+/// let place = |state: &mut Grid, top: usize, left: usize| {
+///     for i in 0..height {
+///         for j in 0..width {
+///             state[(top + i, left + j)] = parent[(i, j)];
+///         }
+///     }
+/// };
+/// place(&mut state, 10, 5);
+/// for _ in 0..151 {
+///     langtons_loop(&mut state, &mut next_state, &table, Boundary::Fixed(0));
+/// }
+///
+/// let mut daughter = Grid::new(height, width);
+/// for i in 0..height {
+///     for j in 0..width {
+///         daughter[(i, j)] = state[(10 + i, 16 + j)];
+///     }
+/// }
+/// assert_eq!(daughter, parent);
+/// // 親のループも、上に腕を伸ばし始めた形で残っている
+/// assert_eq!(state.population(), 2 * parent.population() - 1);
+/// ```
+pub fn langtons_loop(state: &mut Grid, next_state: &mut Grid, table: &RuleTable, boundary: Boundary) {
+    let (height, width) = state.dims();
+    for i in 0..height {
+        for j in 0..width {
+            let neighbors = von_neumann_neighbors(state, i, j, boundary);
+            next_state[(i, j)] = table.next_state(state[(i, j)], neighbors).unwrap_or(state[(i, j)]);
+        }
+    }
+    mem::swap(state, next_state);
+}
+
+/// 表の中の遷移の位置
+fn transition_index(states: u8, center: u8, neighbors: [u8; 4]) -> usize {
+    neighbors
+        .iter()
+        .fold(center as usize, |index, &cell| index * states as usize + cell as usize)
+}
+
+/// セル`(i, j)`の北、東、南、西のセルの状態
+fn von_neumann_neighbors(state: &Grid, i: usize, j: usize, boundary: Boundary) -> [u8; 4] {
+    let (height, width) = state.dims();
+    let cell = |di: isize, dj: isize| match (
+        boundary.index(i as isize + di, height),
+        boundary.index(j as isize + dj, width),
+    ) {
+        (Some(y), Some(x)) => state[(y, x)],
+        _ => boundary.outside_value() as u8,
+    };
+    [cell(-1, 0), cell(0, 1), cell(1, 0), cell(0, -1)]
+}
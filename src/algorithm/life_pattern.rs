@@ -0,0 +1,313 @@
+use algorithm::grid::Grid;
+use algorithm::life_rule::LifeRule;
+use failure;
+use std::fs::File;
+use std::io::prelude::*;
+use std::path::Path;
+
+/// RLEの1行の最大文字数
+const RLE_LINE_LENGTH: usize = 70;
+
+/// ライフゲームのパターン(グライダーや銃など)
+///
+/// パターン集でよく使われるRLE形式(`.rle`)とplaintext形式(`.cells`)から読み込める
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::game_of_life::game_of_life;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_pattern::{to_rle, Pattern};
+/// use my_alife::algorithm::life_rule::LifeRule;
+///
+/// let glider = Pattern::from_rle("#N Glider\nx = 3, y = 3, rule = B3/S23\nbob$2bo$3o!").unwrap();
+/// assert_eq!(glider.name(), Some("Glider"));
+/// assert_eq!(glider.rule(), Some(LifeRule::conway()));
+/// assert_eq!(Pattern::from_plaintext("!Name: Glider\n.O\n..O\nOOO").unwrap().grid(), glider.grid());
+///
+/// let mut state = Grid::new(10, 10);
+/// glider.place(&mut state, 1, 1).unwrap();
+/// let mut next_state = Grid::new(10, 10);
+/// for _ in 0..4 {
+///     game_of_life(&mut state, &mut next_state);
+/// }
+/// // 4世代でグライダーは右下に1セル進む
+/// let mut expected = Grid::new(10, 10);
+/// glider.place(&mut expected, 2, 2).unwrap();
+/// assert_eq!(state, expected);
+/// // 行末の死んだセルは省略される
+/// let rle = to_rle(glider.grid(), LifeRule::conway());
+/// assert_eq!(rle, "x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+/// assert_eq!(Pattern::from_rle(&rle).unwrap().grid(), glider.grid());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    grid: Grid,
+    name: Option<String>,
+    rule: Option<LifeRule>,
+}
+
+impl Pattern {
+    /// RLE形式の文字列から読み込む
+    ///
+    /// * `#`で始まる行はコメント(`#N`はパターンの名前)
+    /// * `x = 3, y = 3, rule = B3/S23`のヘッダーで大きさとルールを指定する(`rule`は省略可)
+    /// * 本体は`<数><タグ>`の並びで、`b`が死、`o`が生、`$`が行の終わり、`!`がパターンの終わり。数を省略すると1
+    pub fn from_rle(s: &str) -> Result<Pattern, failure::Error> {
+        let mut name = None;
+        let mut header = None;
+        let mut body = String::new();
+        for line in s.lines() {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix("#N") {
+                name = Some(rest.trim().to_string());
+            } else if line.starts_with('#') || line.is_empty() {
+                continue;
+            } else if header.is_none() {
+                header = Some(line);
+            } else {
+                body.push_str(line);
+            }
+        }
+        let header = header.ok_or_else(|| failure::err_msg("RLE has no \"x = , y = \" header"))?;
+        let (width, height, rule) = parse_rle_header(header)?;
+
+        let mut grid = Grid::new(height, width);
+        let (mut i, mut j) = (0, 0);
+        let mut count = String::new();
+        for c in body.chars() {
+            if c.is_ascii_digit() {
+                count.push(c);
+                continue;
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+            let run = if count.is_empty() { 1 } else { count.parse::<usize>()? };
+            count.clear();
+            match c {
+                'b' | '.' => j += run,
+                'o' | 'A' => {
+                    if i >= height || j + run > width {
+                        return Err(failure::err_msg(format!(
+                            "cell ({}, {}) is outside of the {}x{} pattern",
+                            i,
+                            j + run - 1,
+                            width,
+                            height
+                        )));
+                    }
+                    for cell in &mut grid.cells_mut()[i * width + j..i * width + j + run] {
+                        *cell = 1;
+                    }
+                    j += run;
+                }
+                '$' => {
+                    i += run;
+                    j = 0;
+                }
+                '!' => break,
+                _ => return Err(failure::err_msg(format!("invalid tag '{}' in RLE", c))),
+            }
+        }
+        Ok(Pattern {
+            grid: grid,
+            name: name,
+            rule: rule,
+        })
+    }
+
+    /// plaintext形式(`.cells`)の文字列から読み込む
+    ///
+    /// `!`で始まる行はコメント(`!Name:`はパターンの名前)。`.`が死、`O`が生のセルで、短い行の右側は死んだセルとみなす
+    pub fn from_plaintext(s: &str) -> Result<Pattern, failure::Error> {
+        let mut name = None;
+        let mut rows = vec![];
+        for line in s.lines() {
+            let line = line.trim();
+            if let Some(rest) = line.strip_prefix("!Name:") {
+                name = Some(rest.trim().to_string());
+            } else if !line.starts_with('!') {
+                rows.push(line);
+            }
+        }
+        let height = rows.len();
+        let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+        let mut grid = Grid::new(height, width);
+        for (i, row) in rows.iter().enumerate() {
+            for (j, c) in row.chars().enumerate() {
+                grid[(i, j)] = match c {
+                    '.' => 0,
+                    'O' | '*' => 1,
+                    _ => return Err(failure::err_msg(format!("invalid cell '{}' in plaintext pattern", c))),
+                };
+            }
+        }
+        Ok(Pattern {
+            grid: grid,
+            name: name,
+            rule: None,
+        })
+    }
+
+    /// ファイルから読み込む。拡張子が`.cells`ならplaintext形式、それ以外はRLE形式とみなす
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Pattern, failure::Error> {
+        let path = path.as_ref();
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("cells") => Pattern::from_plaintext(&contents),
+            _ => Pattern::from_rle(&contents),
+        }
+    }
+
+    /// パターンのセル
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    /// パターンの名前
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// RLEのヘッダーで指定されたルール
+    pub fn rule(&self) -> Option<LifeRule> {
+        self.rule
+    }
+
+    /// パターンの左上が`(top, left)`に来るように`grid`に書き込む
+    ///
+    /// パターンの生きたセルだけを書き込み、死んだセルの場所は`grid`の元の状態のまま
+    ///
+    /// # Arguments
+    /// * `grid` - 書き込む先
+    /// * `top` - パターンの上端の行
+    /// * `left` - パターンの左端の列
+    pub fn place(&self, grid: &mut Grid, top: usize, left: usize) -> Result<(), failure::Error> {
+        let (height, width) = self.grid.dims();
+        if top + height > grid.height() || left + width > grid.width() {
+            return Err(failure::err_msg(format!(
+                "{}x{} pattern at ({}, {}) does not fit in a {}x{} grid",
+                width,
+                height,
+                top,
+                left,
+                grid.width(),
+                grid.height()
+            )));
+        }
+        for i in 0..height {
+            for j in 0..width {
+                if self.grid[(i, j)] != 0 {
+                    grid[(top + i, left + j)] = 1;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `grid`をRLE形式の文字列にする
+///
+/// 行末の死んだセルと末尾の空行は省略し、1行が70文字を超えないように改行する
+pub fn to_rle(grid: &Grid, rule: LifeRule) -> String {
+    // (個数, タグ)の並び
+    let mut runs: Vec<(usize, char)> = vec![];
+    // 次に生きたセルのある行が来たときに書く`$`の数
+    let mut pending_rows = 0;
+    for row in grid.rows() {
+        let len = row.iter().rposition(|cell| *cell != 0).map_or(0, |j| j + 1);
+        if len > 0 {
+            push_run(&mut runs, pending_rows, '$');
+            pending_rows = 0;
+            for cell in &row[..len] {
+                push_run(&mut runs, 1, if *cell != 0 { 'o' } else { 'b' });
+            }
+        }
+        pending_rows += 1;
+    }
+
+    let mut rle = format!("x = {}, y = {}, rule = {}\n", grid.width(), grid.height(), rule);
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let item = if count == 1 {
+            tag.to_string()
+        } else {
+            format!("{}{}", count, tag)
+        };
+        if line.len() + item.len() > RLE_LINE_LENGTH {
+            rle.push_str(&line);
+            rle.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    if line.len() + 1 > RLE_LINE_LENGTH {
+        rle.push_str(&line);
+        rle.push('\n');
+        line.clear();
+    }
+    rle.push_str(&line);
+    rle.push_str("!\n");
+    rle
+}
+
+/// `grid`をRLE形式でファイルに保存する
+pub fn save_rle<P: AsRef<Path>>(path: P, grid: &Grid, rule: LifeRule) -> Result<(), failure::Error> {
+    File::create(path)?.write_all(to_rle(grid, rule).as_bytes())?;
+    Ok(())
+}
+
+/// 直前と同じタグなら個数を足し、違うタグなら新しいrunを追加する
+fn push_run(runs: &mut Vec<(usize, char)>, count: usize, tag: char) {
+    if count == 0 {
+        return;
+    }
+    match runs.last_mut() {
+        Some(last) if last.1 == tag => {
+            last.0 += count;
+            return;
+        }
+        _ => {}
+    }
+    runs.push((count, tag));
+}
+
+/// `x = 3, y = 3, rule = B3/S23`を`(x, y, rule)`にする
+fn parse_rle_header(header: &str) -> Result<(usize, usize, Option<LifeRule>), failure::Error> {
+    let mut x = None;
+    let mut y = None;
+    let mut rule = None;
+    for item in header.split(',') {
+        let mut key_value = item.splitn(2, '=');
+        let key = key_value.next().unwrap_or("").trim();
+        let value = key_value
+            .next()
+            .ok_or_else(|| failure::err_msg(format!("invalid RLE header \"{}\"", header)))?
+            .trim();
+        match key {
+            "x" => x = Some(value.parse::<usize>()?),
+            "y" => y = Some(value.parse::<usize>()?),
+            "rule" => rule = Some(parse_rule(value)?),
+            _ => {}
+        }
+    }
+    match (x, y) {
+        (Some(x), Some(y)) => Ok((x, y, rule)),
+        _ => Err(failure::err_msg(format!("RLE header \"{}\" has no x or y", header))),
+    }
+}
+
+/// `B3/S23`に加えて、古いパターンで使われている`23/3`(S/B記法)も受け付ける
+fn parse_rule(s: &str) -> Result<LifeRule, failure::Error> {
+    if s.contains('B') || s.contains('b') {
+        return s.parse::<LifeRule>();
+    }
+    let parts = s.split('/').collect::<Vec<_>>();
+    if parts.len() != 2 {
+        return Err(failure::err_msg(format!("invalid rule \"{}\"", s)));
+    }
+    format!("B{}/S{}", parts[1], parts[0]).parse::<LifeRule>()
+}
@@ -0,0 +1,130 @@
+use failure;
+use std::fmt;
+use std::str::FromStr;
+
+/// ライフゲーム型(life-like)のルール
+///
+/// 次の状態は自分自身の状態と、ムーア近傍(周囲8セル)のうち生きているセルの数`n`だけで決まる。
+/// * 死んでいるセルは`n`が`birth`に含まれていれば誕生する
+/// * 生きているセルは`n`が`survival`に含まれていれば生き残り、そうでなければ死ぬ
+///
+/// `birth`と`survival`はそれぞれ`n`bit目が立っていれば`n`を含む(0から8なので9bit使う)。
+/// 文字列からは`"B3/S23"`のようなB/S記法でparseできる
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::life_rule::LifeRule;
+///
+/// let high_life = "B36/S23".parse::<LifeRule>().unwrap();
+/// assert_eq!(high_life.next_state(0, 6), 1);
+/// assert_eq!(high_life.next_state(1, 6), 0);
+/// assert_eq!(high_life.to_string(), "B36/S23");
+/// // Seedsは生き残るセルがない
+/// assert_eq!("B2/S".parse::<LifeRule>().unwrap().next_state(1, 2), 0);
+/// assert_eq!("b3/s23".parse::<LifeRule>().unwrap(), LifeRule::conway());
+/// assert!("B9/S23".parse::<LifeRule>().is_err());
+/// assert!("B3S23".parse::<LifeRule>().is_err());
+/// assert!("S23/B3".parse::<LifeRule>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LifeRule {
+    birth: u16,
+    survival: u16,
+}
+
+impl LifeRule {
+    /// LifeRuleインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `birth` - 誕生する生きたセルの数の一覧(0から8)
+    /// * `survival` - 生き残る生きたセルの数の一覧(0から8)
+    pub fn new(birth: &[u8], survival: &[u8]) -> Result<LifeRule, failure::Error> {
+        Ok(LifeRule {
+            birth: Self::to_mask(birth)?,
+            survival: Self::to_mask(survival)?,
+        })
+    }
+
+    /// コンウェイのライフゲーム(B3/S23)
+    pub fn conway() -> LifeRule {
+        LifeRule {
+            birth: 1 << 3,
+            survival: (1 << 2) | (1 << 3),
+        }
+    }
+
+    /// 生きたセルが`n`個のとき、死んでいるセルが誕生するかどうか
+    pub fn is_born(&self, n: u8) -> bool {
+        (self.birth >> n) & 1 == 1
+    }
+
+    /// 生きたセルが`n`個のとき、生きているセルが生き残るかどうか
+    pub fn survives(&self, n: u8) -> bool {
+        (self.survival >> n) & 1 == 1
+    }
+
+    /// 次の状態
+    ///
+    /// # Arguments
+    /// * `cell` - 今の状態(0が死、1が生)
+    /// * `neighbor_cell_sum` - 周囲8セルのうち生きているセルの数
+    pub fn next_state(&self, cell: u8, neighbor_cell_sum: u8) -> u8 {
+        let alive = if cell == 0 {
+            self.is_born(neighbor_cell_sum)
+        } else {
+            self.survives(neighbor_cell_sum)
+        };
+        alive as u8
+    }
+
+    fn to_mask(counts: &[u8]) -> Result<u16, failure::Error> {
+        let mut mask = 0;
+        for &n in counts {
+            if n > 8 {
+                return Err(failure::err_msg(format!("neighbor count {} is out of range 0-8", n)));
+            }
+            mask |= 1 << n;
+        }
+        Ok(mask)
+    }
+
+    fn counts(mask: u16) -> String {
+        (0..9).filter(|n| (mask >> n) & 1 == 1).map(|n| n.to_string()).collect()
+    }
+}
+
+impl FromStr for LifeRule {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<LifeRule, failure::Error> {
+        let upper = s.trim().to_uppercase();
+        let parts = upper.split('/').collect::<Vec<_>>();
+        if parts.len() != 2 {
+            return Err(failure::err_msg(format!("\"{}\" is not in \"B.../S...\" form", s)));
+        }
+        let parse_counts = |part: &str, prefix: char| -> Result<Vec<u8>, failure::Error> {
+            if !part.starts_with(prefix) {
+                return Err(failure::err_msg(format!(
+                    "\"{}\" does not start with '{}'",
+                    part, prefix
+                )));
+            }
+            part[1..]
+                .chars()
+                .map(|c| match c.to_digit(10) {
+                    Some(n) if n <= 8 => Ok(n as u8),
+                    _ => Err(failure::err_msg(format!("invalid neighbor count '{}' in \"{}\"", c, s))),
+                })
+                .collect()
+        };
+        LifeRule::new(&parse_counts(parts[0], 'B')?, &parse_counts(parts[1], 'S')?)
+    }
+}
+
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "B{}/S{}", Self::counts(self.birth), Self::counts(self.survival))
+    }
+}
@@ -1,6 +1,48 @@
+/// 境界条件
+pub mod boundary;
 /// セルラーオートマトンのアルゴリズム
 pub mod cellular_automata;
+/// 1次元セルラーオートマトンの初期状態
+pub mod initial_state;
+/// 近傍半径と状態数を一般化したセルラーオートマトンのアルゴリズム
+pub mod generalized_cellular_automata;
+/// 総和型・外総和型のセルラーオートマトンのアルゴリズム
+pub mod totalistic_cellular_automata;
+/// 非同期・確率的に更新するセルラーオートマトンのアルゴリズム
+pub mod stochastic_cellular_automata;
+/// 時間を逆向きにたどれる2階のセルラーオートマトンのアルゴリズム
+pub mod reversible_cellular_automata;
+/// 1セル1bitで計算するセルラーオートマトンのアルゴリズム
+pub mod bit_packed_cellular_automata;
+/// 密度分類問題を解くルールを遺伝的アルゴリズムで進化させる
+pub mod density_classification;
+/// 基本ルール256通りを調査してクラス分類する
+pub mod rule_survey;
+/// 2次元セルラーオートマトンの格子
+pub mod grid;
 /// LifeGameのアルゴリズム
 pub mod game_of_life;
+/// B/S記法で書くライフゲーム型のルール
+pub mod life_rule;
+/// ライフゲームのパターンファイル(RLE、plaintext)の読み書き
+pub mod life_pattern;
+/// 1セル1bitで計算するライフゲームのアルゴリズム
+pub mod bit_packed_game_of_life;
+/// 4分木とメモ化で長い世代を高速に計算するライフゲームのアルゴリズム(HashLife)
+pub mod hashlife;
+/// 生きているセルだけを持つ、端のない盤面のライフゲーム
+pub mod sparse_life;
+/// ランダムな盤面が安定した後に残る物体(固定物体、振動子、宇宙船)の分類と集計
+pub mod soup_census;
+/// Generations型のルールやWireworldなど、3状態以上の2次元セルラーオートマトンのアルゴリズム
+pub mod multi_state_automata;
+/// 状態遷移表を使ったLangtonの自己複製ループのアルゴリズム
+pub mod langtons_loop;
+/// ラングトンのアリなど、盤面を塗り替えながら動き回るターマイト(2次元のチューリングマシン)
+pub mod turmite;
+/// 高速フーリエ変換(FFT)
+pub mod fft;
+/// LeniaやSmoothLifeなど、連続な状態と連続な近傍を持つセルラーオートマトンのアルゴリズム
+pub mod continuous_automata;
 /// GrayScottモデルのアルゴリズム
 pub mod gray_scott;
@@ -0,0 +1,271 @@
+use algorithm::boundary::Boundary;
+use algorithm::grid::Grid;
+use algorithm::life_rule::LifeRule;
+use failure;
+use std::fmt;
+use std::mem;
+use std::str::FromStr;
+
+/// Wireworldの空のセル
+pub const WIREWORLD_EMPTY: u8 = 0;
+/// Wireworldの電子の頭
+pub const ELECTRON_HEAD: u8 = 1;
+/// Wireworldの電子の尾
+pub const ELECTRON_TAIL: u8 = 2;
+/// Wireworldの導線
+pub const CONDUCTOR: u8 = 3;
+
+/// Generations型のルール
+///
+/// ライフゲーム型のルールに、死ぬまでの「不応期」の状態を加えたもの。状態数を`states`とすると
+/// * 0は死んでいるセルで、生きている(状態1の)近傍の数`n`が`birth`に含まれていれば1になる
+/// * 1は生きているセルで、`n`が`survival`に含まれていれば1のまま、そうでなければ2になる
+/// * 2から`states - 1`は死につつあるセルで、近傍によらず1ずつ増え、`states - 1`の次は0に戻る
+///
+/// `states`が2のときはライフゲーム型のルールと同じになる。
+/// 文字列からは`"345/2/4"`(S/B/C)か`"B2/S345/C4"`の形でparseできる
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::multi_state_automata::GenerationsRule;
+///
+/// let brians_brain = "/2/3".parse::<GenerationsRule>().unwrap();
+/// assert_eq!(brians_brain, GenerationsRule::brians_brain());
+/// assert_eq!("B2/S/C3".parse::<GenerationsRule>().unwrap(), brians_brain);
+/// // 生き残れないので生きたセルは次に不応期(2)になり、その次に死ぬ
+/// assert_eq!(brians_brain.next_state(1, 2), 2);
+/// assert_eq!(brians_brain.next_state(2, 2), 0);
+/// assert_eq!(brians_brain.next_state(0, 2), 1);
+/// // 範囲外の状態は死んだセルに戻る
+/// assert_eq!(brians_brain.next_state(255, 2), 0);
+/// assert_eq!(GenerationsRule::star_wars().to_string(), "345/2/4");
+/// assert!("345/2/1".parse::<GenerationsRule>().is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GenerationsRule {
+    life_rule: LifeRule,
+    states: u8,
+}
+
+impl GenerationsRule {
+    /// GenerationsRuleインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `birth` - 誕生する生きたセルの数の一覧(0から8)
+    /// * `survival` - 生き残る生きたセルの数の一覧(0から8)
+    /// * `states` - 死んだ状態と生きた状態を含む状態数(2以上)
+    pub fn new(birth: &[u8], survival: &[u8], states: u8) -> Result<GenerationsRule, failure::Error> {
+        Self::from_life_rule(LifeRule::new(birth, survival)?, states)
+    }
+
+    fn from_life_rule(life_rule: LifeRule, states: u8) -> Result<GenerationsRule, failure::Error> {
+        if states < 2 {
+            return Err(failure::err_msg(format!(
+                "a Generations rule needs at least 2 states, not {}",
+                states
+            )));
+        }
+        Ok(GenerationsRule {
+            life_rule: life_rule,
+            states: states,
+        })
+    }
+
+    /// Brian's Brain(/2/3)
+    pub fn brians_brain() -> GenerationsRule {
+        GenerationsRule::new(&[2], &[], 3).unwrap()
+    }
+
+    /// Star Wars(345/2/4)
+    pub fn star_wars() -> GenerationsRule {
+        GenerationsRule::new(&[2], &[3, 4, 5], 4).unwrap()
+    }
+
+    /// 誕生・生存の条件
+    pub fn life_rule(&self) -> LifeRule {
+        self.life_rule
+    }
+
+    /// 状態数
+    pub fn states(&self) -> u8 {
+        self.states
+    }
+
+    /// 次の状態
+    ///
+    /// 最後の状態(`states - 1`)と、ルールの範囲外の状態(`states`以上)の次は0になる
+    ///
+    /// # Arguments
+    /// * `cell` - 今の状態
+    /// * `alive_neighbors` - 周囲8セルのうち生きている(状態1の)セルの数
+    pub fn next_state(&self, cell: u8, alive_neighbors: u8) -> u8 {
+        match cell {
+            0 => self.life_rule.is_born(alive_neighbors) as u8,
+            1 if self.life_rule.survives(alive_neighbors) => 1,
+            _ if cell < self.states - 1 => cell + 1,
+            _ => 0,
+        }
+    }
+}
+
+impl FromStr for GenerationsRule {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<GenerationsRule, failure::Error> {
+        let upper = s.trim().to_uppercase();
+        let parts = upper.split('/').collect::<Vec<_>>();
+        if parts.len() != 3 {
+            return Err(failure::err_msg(format!(
+                "\"{}\" is not in \"S/B/C\" or \"B.../S.../C...\" form",
+                s
+            )));
+        }
+        let (life_rule, states) = if parts[0].starts_with('B') {
+            if !parts[2].starts_with('C') {
+                return Err(failure::err_msg(format!("\"{}\" does not start with 'C'", parts[2])));
+            }
+            (
+                format!("{}/{}", parts[0], parts[1]).parse::<LifeRule>()?,
+                &parts[2][1..],
+            )
+        } else {
+            (format!("B{}/S{}", parts[1], parts[0]).parse::<LifeRule>()?, parts[2])
+        };
+        Self::from_life_rule(life_rule, states.parse::<u8>()?)
+    }
+}
+
+impl fmt::Display for GenerationsRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let survival = (0..9)
+            .filter(|&n| self.life_rule.survives(n))
+            .map(|n| n.to_string())
+            .collect::<String>();
+        let birth = (0..9)
+            .filter(|&n| self.life_rule.is_born(n))
+            .map(|n| n.to_string())
+            .collect::<String>();
+        write!(f, "{}/{}/{}", survival, birth, self.states)
+    }
+}
+
+/// Generations型のルールの2次元セルラーオートマトン
+///
+/// 現在のstateを元に次の瞬間のstate(next_state)を計算しstateとnext_stateを入れ替える
+///
+/// # Arguments
+/// * `state` - 現在の状態(0から`rule.states() - 1`)
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+/// * `rule` - Generations型のルール
+/// * `boundary` - 境界条件
+///
+/// # Example
+/// 状態数2ならライフゲームと同じになる
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::game_of_life::game_of_life_with_rule;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::life_rule::LifeRule;
+/// use my_alife::algorithm::multi_state_automata::{generations, GenerationsRule};
+///
+/// let mut state = Grid::random(16, 12, 0.4, 3);
+/// let mut expected = state.clone();
+/// let mut next_state = Grid::new(16, 12);
+/// let rule = "23/3/2".parse::<GenerationsRule>().unwrap();
+/// for _ in 0..20 {
+///     generations(&mut state, &mut next_state, rule, Boundary::Periodic);
+///     game_of_life_with_rule(&mut expected, &mut next_state, LifeRule::conway(), Boundary::Periodic);
+///     assert_eq!(state, expected);
+/// }
+/// ```
+pub fn generations(state: &mut Grid, next_state: &mut Grid, rule: GenerationsRule, boundary: Boundary) {
+    let (height, width) = state.dims();
+    for i in 0..height {
+        for j in 0..width {
+            let alive_neighbors = count_neighbors(state, i, j, 1, boundary);
+            next_state[(i, j)] = rule.next_state(state[(i, j)], alive_neighbors);
+        }
+    }
+    mem::swap(state, next_state);
+}
+
+/// Wireworld
+///
+/// 電子回路を表す4状態の2次元セルラーオートマトン
+/// * 空(`WIREWORLD_EMPTY`)は空のまま
+/// * 電子の頭(`ELECTRON_HEAD`)は電子の尾(`ELECTRON_TAIL`)になる
+/// * 電子の尾は導線(`CONDUCTOR`)に戻る
+/// * 導線は周囲8セルのうち電子の頭がちょうど1つか2つなら電子の頭になる
+///
+/// # Arguments
+/// * `state` - 現在の状態
+/// * `next_state` - 次の瞬間の状態(`state`と同じ大きさ)
+/// * `boundary` - 境界条件
+///
+/// # Example
+/// 導線を電子が1世代に1セルずつ進む
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::algorithm::grid::Grid;
+/// use my_alife::algorithm::multi_state_automata::{parse_wireworld, wireworld};
+///
+/// let mut state = parse_wireworld("tH######").unwrap();
+/// let mut next_state = Grid::new(1, 8);
+/// for _ in 0..3 {
+///     wireworld(&mut state, &mut next_state, Boundary::Fixed(0));
+/// }
+/// assert_eq!(state, parse_wireworld("###tH###").unwrap());
+/// ```
+pub fn wireworld(state: &mut Grid, next_state: &mut Grid, boundary: Boundary) {
+    let (height, width) = state.dims();
+    for i in 0..height {
+        for j in 0..width {
+            next_state[(i, j)] = match state[(i, j)] {
+                ELECTRON_HEAD => ELECTRON_TAIL,
+                ELECTRON_TAIL => CONDUCTOR,
+                CONDUCTOR => match count_neighbors(state, i, j, ELECTRON_HEAD, boundary) {
+                    1 | 2 => ELECTRON_HEAD,
+                    _ => CONDUCTOR,
+                },
+                _ => WIREWORLD_EMPTY,
+            };
+        }
+    }
+    mem::swap(state, next_state);
+}
+
+/// 文字で描いたWireworldの回路をGridにする
+///
+/// `H`が電子の頭、`t`が電子の尾、`#`が導線、空白と`.`が空のセル。短い行の右側は空のセルとみなす
+pub fn parse_wireworld(s: &str) -> Result<Grid, failure::Error> {
+    let rows = s.lines().collect::<Vec<_>>();
+    let height = rows.len();
+    let width = rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let mut grid = Grid::new(height, width);
+    for (i, row) in rows.iter().enumerate() {
+        for (j, c) in row.chars().enumerate() {
+            grid[(i, j)] = match c {
+                ' ' | '.' => WIREWORLD_EMPTY,
+                'H' => ELECTRON_HEAD,
+                't' => ELECTRON_TAIL,
+                '#' => CONDUCTOR,
+                _ => return Err(failure::err_msg(format!("invalid Wireworld cell '{}'", c))),
+            };
+        }
+    }
+    Ok(grid)
+}
+
+/// 周囲8セルのうち状態が`target`のセルの数
+fn count_neighbors(state: &Grid, i: usize, j: usize, target: u8, boundary: Boundary) -> u8 {
+    state
+        .neighbors_with_boundary(i, j, boundary)
+        .filter(|&cell| cell == target)
+        .count() as u8
+}
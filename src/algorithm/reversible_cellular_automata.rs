@@ -0,0 +1,72 @@
+use ndarray::Array1;
+use std::mem;
+
+/// 2階の可逆セルラーオートマトン(Fredkinの方法)
+///
+/// 基本ルールで求めた次の状態と、1つ前のフェーズの状態の排他的論理和を次の状態にする。  
+/// `next = rule(current) XOR previous`  
+/// 逆に`previous = rule(current) XOR next`なので、どんなルールから作っても時間を逆向きにたどれる。
+///
+/// `cellular_automata`と同じ`(Array1<u32>, Array1<u32>)`の組を使うが、
+/// `state.0`が現在の状態、`state.1`が1つ前の状態を表す。
+/// 計算後は`state.0`が次の状態、`state.1`が現在の状態になる。
+/// 引数も`cellular_automata`と同じなので、そのまま`ArrayVisualizer::draw_loop`に渡せる
+///
+/// # Arguments
+/// * `state` - 現在の状態と1つ前の状態の組
+/// * `rule` - ウルフラムのルールコーディングの数字
+/// * `space_size` - セルの数
+///
+/// # Example
+/// 進めた分だけ戻すと初期状態に戻る
+/// ```
+/// extern crate ndarray;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::reversible_cellular_automata::{
+///     second_order_cellular_automata, second_order_cellular_automata_backward,
+/// };
+/// use ndarray::Array1;
+///
+/// let space_size = 100;
+/// let current = Array1::from_vec((0..space_size).map(|i| (i * i % 7 % 2) as u32).collect());
+/// let previous = Array1::from_vec((0..space_size).map(|i| (i % 5 % 2) as u32).collect());
+/// for rule in 0..256 {
+///     let mut state = (current.clone(), previous.clone());
+///     for _ in 0..50 {
+///         second_order_cellular_automata(&mut state, rule as u8, space_size);
+///     }
+///     for _ in 0..50 {
+///         second_order_cellular_automata_backward(&mut state, rule as u8, space_size);
+///     }
+///     assert_eq!(state, (current.clone(), previous.clone()));
+/// }
+/// ```
+pub fn second_order_cellular_automata(state: &mut (Array1<u32>, Array1<u32>), rule: u8, space_size: usize) {
+    let current_state = &mut state.0;
+    let previous_state = &mut state.1;
+    for i in 0..space_size {
+        let l = current_state[(i + space_size - 1) % space_size];
+        let c = current_state[i];
+        let r = current_state[(i + 1) % space_size];
+        let neighbor_cell_code = 4 * l + 2 * c + r;
+        // 1つ前の状態はもう使わないので、その場で次の状態に書き換える
+        previous_state[i] ^= ((rule >> neighbor_cell_code) & 1) as u32;
+    }
+    mem::swap(current_state, previous_state);
+}
+
+/// `second_order_cellular_automata`の時間を1フェーズ戻す
+///
+/// `state.0`が現在の状態、`state.1`が1つ前の状態で、計算後は`state.0`が1つ前の状態、`state.1`が2つ前の状態になる。
+/// 現在と1つ前を入れ替えて1フェーズ進め、もう一度入れ替えれば良い
+///
+/// # Arguments
+/// * `state` - 現在の状態と1つ前の状態の組
+/// * `rule` - ウルフラムのルールコーディングの数字
+/// * `space_size` - セルの数
+pub fn second_order_cellular_automata_backward(state: &mut (Array1<u32>, Array1<u32>), rule: u8, space_size: usize) {
+    mem::swap(&mut state.0, &mut state.1);
+    second_order_cellular_automata(state, rule, space_size);
+    mem::swap(&mut state.0, &mut state.1);
+}
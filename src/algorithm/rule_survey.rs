@@ -0,0 +1,281 @@
+use algorithm::cellular_automata::cellular_automata;
+use algorithm::initial_state::random;
+use ndarray::Array1;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::fmt;
+
+/// ウルフラムのクラス分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WolframClass {
+    /// 一様な状態に落ち着く
+    I,
+    /// 固定点か短い周期の振動に落ち着く
+    II,
+    /// カオス的に振る舞い続ける
+    III,
+    /// 局所構造が長く生き残る複雑な振る舞い
+    IV,
+}
+
+impl fmt::Display for WolframClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            WolframClass::I => "I",
+            WolframClass::II => "II",
+            WolframClass::III => "III",
+            WolframClass::IV => "IV",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// ルール調査の設定
+#[derive(Debug, Clone, Copy)]
+pub struct SurveyConfig {
+    /// セルの数。2の累乗だとルール90のような加法的ルールがすぐ0に落ちるので避けたほうが良い
+    pub space_size: usize,
+    /// 1回の試行で何フェーズ計算するか
+    pub steps: usize,
+    /// ルールごとに何通りのランダムな初期状態で試すか
+    pub trials: usize,
+    /// 初期状態を作る乱数のseed
+    pub seed: usize,
+}
+
+impl Default for SurveyConfig {
+    fn default() -> SurveyConfig {
+        SurveyConfig {
+            space_size: 149,
+            steps: 512,
+            trials: 8,
+            seed: 0,
+        }
+    }
+}
+
+/// 1つのルールの統計量。各値は全試行の平均
+#[derive(Debug, Clone)]
+pub struct RuleStatistics {
+    /// ルール番号
+    pub rule: u8,
+    /// 最終状態の密度(状態1のセルの割合)
+    pub density: f64,
+    /// 最終状態の空間エントロピー(長さ3のブロックのエントロピーを3bitで割って0から1にしたもの)
+    pub spatial_entropy: f64,
+    /// 各セルの時系列のエントロピー(後半`steps / 2`フェーズ分)
+    pub temporal_entropy: f64,
+    /// 周期解に入るまでのフェーズ数。周期解が見つからなかった試行は除く
+    pub transient_length: Option<f64>,
+    /// 周期解の周期。周期解が見つからなかった試行は除く
+    pub period: Option<f64>,
+    /// 周期解が見つかった試行の割合
+    pub cycle_ratio: f64,
+    /// 上記の統計量から推定したクラス
+    pub class: WolframClass,
+}
+
+/// 256通りの基本ルールを全て調査する
+///
+/// ルールごとに`config.trials`通りのランダムな初期状態から`cellular_automata`で`config.steps`フェーズ計算し、
+/// 統計量とクラスを求める。ルールごとにrayonで並列に計算するが、結果はseedだけで決まる
+pub fn survey(config: &SurveyConfig) -> Vec<RuleStatistics> {
+    (0..256usize)
+        .into_par_iter()
+        .map(|rule| survey_rule(rule as u8, config))
+        .collect()
+}
+
+/// 1つの基本ルールを調査する
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::rule_survey::{survey_rule, SurveyConfig, WolframClass};
+///
+/// let config = SurveyConfig::default();
+/// assert_eq!(survey_rule(0, &config).class, WolframClass::I);
+/// assert_eq!(survey_rule(4, &config).class, WolframClass::II);
+/// assert_eq!(survey_rule(30, &config).class, WolframClass::III);
+/// assert_eq!(survey_rule(110, &config).class, WolframClass::IV);
+/// ```
+pub fn survey_rule(rule: u8, config: &SurveyConfig) -> RuleStatistics {
+    let trials = (0..config.trials)
+        .map(|trial| run_trial(rule, config, config.seed + rule as usize * config.trials + trial))
+        .collect::<Vec<_>>();
+    let cycles = trials.iter().filter_map(|trial| trial.cycle).collect::<Vec<_>>();
+    let density = mean(trials.iter().map(|trial| trial.density));
+    let spatial_entropy = mean(trials.iter().map(|trial| trial.spatial_entropy));
+    let temporal_entropy = mean(trials.iter().map(|trial| trial.temporal_entropy));
+    let uniformity = mean(trials.iter().map(|trial| trial.uniformity));
+    let transient_length = if cycles.is_empty() {
+        None
+    } else {
+        Some(mean(cycles.iter().map(|cycle| cycle.0 as f64)))
+    };
+    let period = if cycles.is_empty() {
+        None
+    } else {
+        Some(mean(cycles.iter().map(|cycle| cycle.1 as f64)))
+    };
+    let cycle_ratio = cycles.len() as f64 / trials.len() as f64;
+
+    let class = if cycle_ratio < 1.0 {
+        // 周期解に入らないものは、どのセルも同じように乱雑ならクラスIII、そうでなければ局所構造が残っているクラスIV
+        if spatial_entropy > 0.8 && uniformity > 0.985 {
+            WolframClass::III
+        } else {
+            WolframClass::IV
+        }
+    } else if transient_length.unwrap_or(0.0) > (config.steps / 8) as f64 {
+        WolframClass::IV
+    } else if period == Some(1.0) && spatial_entropy < 0.01 {
+        WolframClass::I
+    } else {
+        WolframClass::II
+    };
+
+    RuleStatistics {
+        rule: rule,
+        density: density,
+        spatial_entropy: spatial_entropy,
+        temporal_entropy: temporal_entropy,
+        transient_length: transient_length,
+        period: period,
+        cycle_ratio: cycle_ratio,
+        class: class,
+    }
+}
+
+/// 調査結果をCSVにする
+pub fn to_csv(statistics: &[RuleStatistics]) -> String {
+    let mut csv =
+        String::from("rule,density,spatial_entropy,temporal_entropy,transient_length,period,cycle_ratio,class\n");
+    for s in statistics {
+        csv.push_str(&format!(
+            "{},{:.4},{:.4},{:.4},{},{},{:.4},{}\n",
+            s.rule,
+            s.density,
+            s.spatial_entropy,
+            s.temporal_entropy,
+            s.transient_length.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+            s.period.map(|v| format!("{:.2}", v)).unwrap_or_default(),
+            s.cycle_ratio,
+            s.class
+        ));
+    }
+    csv
+}
+
+/// 調査結果をJSONにする。周期解が見つからなかった値は`null`になる
+pub fn to_json(statistics: &[RuleStatistics]) -> String {
+    let rows = statistics
+        .iter()
+        .map(|s| {
+            format!(
+                "  {{\"rule\": {}, \"density\": {:.4}, \"spatial_entropy\": {:.4}, \"temporal_entropy\": {:.4}, \
+                 \"transient_length\": {}, \"period\": {}, \"cycle_ratio\": {:.4}, \"class\": \"{}\"}}",
+                s.rule,
+                s.density,
+                s.spatial_entropy,
+                s.temporal_entropy,
+                s.transient_length
+                    .map(|v| format!("{:.2}", v))
+                    .unwrap_or_else(|| "null".to_string()),
+                s.period
+                    .map(|v| format!("{:.2}", v))
+                    .unwrap_or_else(|| "null".to_string()),
+                s.cycle_ratio,
+                s.class
+            )
+        })
+        .collect::<Vec<_>>();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+/// 1回の試行の結果
+struct Trial {
+    density: f64,
+    spatial_entropy: f64,
+    temporal_entropy: f64,
+    /// `temporal_entropy`を観測期間全体の密度から求めたエントロピーで割ったもの。
+    /// 全てのセルが同じ頻度で明滅していれば1で、局所構造が居座るとセルごとに頻度が偏って小さくなる
+    uniformity: f64,
+    /// 周期解に入るまでのフェーズ数と周期
+    cycle: Option<(usize, usize)>,
+}
+
+fn run_trial(rule: u8, config: &SurveyConfig, seed: usize) -> Trial {
+    let space_size = config.space_size;
+    let mut state = (random(space_size, 0.5, seed), Array1::<u32>::zeros(space_size));
+
+    // 各状態が最初に現れたフェーズを覚えておき、同じ状態が再び現れたら周期解に入ったとみなす
+    let mut visited = HashMap::new();
+    visited.insert(state.0.to_vec(), 0);
+    let mut cycle = None;
+    let mut ones = vec![0usize; space_size];
+    let observation_start = config.steps - config.steps / 2;
+    for step in 1..config.steps + 1 {
+        cellular_automata(&mut state, rule, space_size);
+        if step > observation_start {
+            for (count, cell) in ones.iter_mut().zip(state.0.iter()) {
+                *count += *cell as usize;
+            }
+        }
+        if cycle.is_none() {
+            if let Some(first) = visited.insert(state.0.to_vec(), step) {
+                cycle = Some((first, step - first));
+            }
+        }
+    }
+
+    let observed = (config.steps - observation_start).max(1) as f64;
+    let temporal_entropy = mean(ones.iter().map(|count| binary_entropy(*count as f64 / observed)));
+    let overall_entropy = binary_entropy(ones.iter().sum::<usize>() as f64 / observed / space_size as f64);
+    Trial {
+        density: state.0.iter().sum::<u32>() as f64 / space_size as f64,
+        spatial_entropy: block_entropy(&state.0, 3) / 3.0,
+        temporal_entropy: temporal_entropy,
+        uniformity: if overall_entropy > 0.0 {
+            temporal_entropy / overall_entropy
+        } else {
+            1.0
+        },
+        cycle: cycle,
+    }
+}
+
+/// 周期境界で長さ`block_size`のブロックの出現頻度から求めたエントロピー(bit)
+fn block_entropy(state: &Array1<u32>, block_size: usize) -> f64 {
+    let space_size = state.len();
+    let mut counts = HashMap::new();
+    for i in 0..space_size {
+        let block = (0..block_size).fold(0, |code, j| code * 2 + state[(i + j) % space_size]);
+        *counts.entry(block).or_insert(0usize) += 1;
+    }
+    counts
+        .values()
+        .map(|count| {
+            let p = *count as f64 / space_size as f64;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+fn binary_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        0.0
+    } else {
+        -p * p.log2() - (1.0 - p) * (1.0 - p).log2()
+    }
+}
+
+fn mean<I: Iterator<Item = f64>>(values: I) -> f64 {
+    let (sum, count) = values.fold((0.0, 0), |(sum, count), v| (sum + v, count + 1));
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
@@ -0,0 +1,541 @@
+use algorithm::grid::Grid;
+use algorithm::life_rule::LifeRule;
+use algorithm::sparse_life::SparseLife;
+use failure;
+use rand::{Rng, SeedableRng, StdRng};
+use rayon::prelude::*;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// Extended Wechsler形式で1列(5セル)の値を表す文字
+const COLUMN_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuv";
+/// Extended Wechsler形式で連続する0の数(4から39)を表す`y`の後の文字
+const ZERO_RUN_CHARS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+
+/// ムーア近傍(周囲8セル)の相対座標
+const NEIGHBOR_OFFSETS: [(i64, i64); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// よく現れる物体のapgcodeと名前
+const KNOWN_OBJECTS: &[(&str, &str)] = &[
+    ("xs4_33", "block"),
+    ("xs6_696", "beehive"),
+    ("xs7_2596", "loaf"),
+    ("xs5_253", "boat"),
+    ("xs6_356", "ship"),
+    ("xs4_252", "tub"),
+    ("xs8_6996", "pond"),
+    ("xp2_7", "blinker"),
+    ("xp2_7e", "toad"),
+    ("xp2_318c", "beacon"),
+    ("xq4_153", "glider"),
+    ("xq4_6frc", "lightweight spaceship"),
+];
+
+/// 安定した後の物体の種類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ObjectClass {
+    /// 固定物体(変化しない)
+    StillLife,
+    /// 振動子。`period`世代ごとに同じ形・同じ場所に戻る
+    Oscillator {
+        /// 周期
+        period: u64,
+    },
+    /// 宇宙船。`period`世代ごとに同じ形に戻り、`(dy, dx)`セル移動する
+    Spaceship {
+        /// 周期
+        period: u64,
+        /// 1周期で下に動くセルの数
+        dy: i64,
+        /// 1周期で右に動くセルの数
+        dx: i64,
+    },
+    /// `max_period`世代以内に同じ形に戻らなかった物体(近くの物体とまだ反応しているものなど)
+    Unidentified,
+}
+
+impl fmt::Display for ObjectClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ObjectClass::StillLife => write!(f, "still life"),
+            ObjectClass::Oscillator { period } => write!(f, "oscillator (p{})", period),
+            ObjectClass::Spaceship { period, dy, dx } => {
+                let direction = if dy != 0 && dx != 0 { "diagonal" } else { "orthogonal" };
+                // 速さは約分して`c/4`、`2c/5`のように書く
+                let displacement = dy.abs().max(dx.abs()) as u64;
+                let (mut a, mut b) = (displacement, period);
+                while b != 0 {
+                    let r = a % b;
+                    a = b;
+                    b = r;
+                }
+                match (displacement / a, period / a) {
+                    (1, period) => write!(f, "spaceship (c/{} {})", period, direction),
+                    (speed, period) => write!(f, "spaceship ({}c/{} {})", speed, period, direction),
+                }
+            }
+            ObjectClass::Unidentified => write!(f, "unidentified"),
+        }
+    }
+}
+
+/// 盤面から切り出した1つの物体
+#[derive(Debug, Clone, PartialEq)]
+pub struct CensusObject {
+    /// apgsearchと同じ形式の名前(`xs4_33`など)。回転・反転・位相によらず同じ物体は同じ名前になる
+    pub apgcode: String,
+    /// 物体の種類
+    pub class: ObjectClass,
+    /// 盤面での生きたセルの座標
+    pub cells: Vec<(i64, i64)>,
+}
+
+/// 物体の種類ごとの数
+#[derive(Debug, Clone, PartialEq)]
+pub struct Census {
+    /// ルール
+    pub rule: LifeRule,
+    /// 調べた盤面の数
+    pub soups: usize,
+    /// 安定するまでにかかった世代数の合計
+    pub generations: u64,
+    /// apgcodeごとの(種類, 数)
+    pub counts: BTreeMap<String, (ObjectClass, usize)>,
+}
+
+impl Census {
+    fn new(rule: LifeRule) -> Census {
+        Census {
+            rule: rule,
+            soups: 0,
+            generations: 0,
+            counts: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, generations: u64, objects: &[CensusObject]) {
+        self.soups += 1;
+        self.generations += generations;
+        for object in objects {
+            self.counts.entry(object.apgcode.clone()).or_insert((object.class, 0)).1 += 1;
+        }
+    }
+
+    fn merge(mut self, other: Census) -> Census {
+        self.soups += other.soups;
+        self.generations += other.generations;
+        for (apgcode, (class, count)) in other.counts {
+            self.counts.entry(apgcode).or_insert((class, 0)).1 += count;
+        }
+        self
+    }
+
+    /// apgsearchの結果に似た形式のレポート。数の多い順に`apgcode 数 種類 (名前)`を並べる
+    pub fn report(&self) -> String {
+        let mut report = format!(
+            "@RULE {}\n@NUM_SOUPS {}\n@NUM_GENERATIONS {}\n\n@CENSUS TABLE\n",
+            self.rule, self.soups, self.generations
+        );
+        let mut rows = self.counts.iter().collect::<Vec<_>>();
+        rows.sort_by(|a, b| (b.1).1.cmp(&(a.1).1).then(a.0.cmp(b.0)));
+        for (apgcode, &(class, count)) in rows {
+            let name = KNOWN_OBJECTS
+                .iter()
+                .find(|known| known.0 == apgcode.as_str())
+                .map_or(String::new(), |known| format!(" ({})", known.1));
+            report.push_str(&format!("{} {} {}{}\n", apgcode, count, class, name));
+        }
+        report
+    }
+}
+
+/// ランダムな盤面(soup)を沢山作って調べるときの設定
+#[derive(Debug, Clone, Copy)]
+pub struct SoupConfig {
+    /// 盤面の数
+    pub soups: usize,
+    /// soupの一辺のセルの数(apgsearchと同じ16x16が既定値)
+    pub soup_size: usize,
+    /// soupの各セルが生きている確率
+    pub density: f64,
+    /// 安定するのを待つ最大の世代数
+    pub max_generations: u64,
+    /// 振動子・宇宙船として調べる最大の周期
+    pub max_period: u64,
+    /// 乱数のseed
+    pub seed: usize,
+}
+
+impl Default for SoupConfig {
+    fn default() -> SoupConfig {
+        SoupConfig {
+            soups: 100,
+            soup_size: 16,
+            density: 0.5,
+            max_generations: 10000,
+            max_period: 64,
+            seed: 0,
+        }
+    }
+}
+
+/// ランダムな盤面を`config.soups`個作って、安定した後の物体を数える(rayonで並列に計算する)
+///
+/// soupは端のない盤面に置くので、放出されたグライダーは折り返さずにそのまま数えられる
+pub fn soup_census(rule: LifeRule, config: &SoupConfig) -> Result<Census, failure::Error> {
+    // B0のルールはSparseLifeで扱えないので先に確かめておく
+    SparseLife::new(rule)?;
+    let census = (0..config.soups)
+        .into_par_iter()
+        .map(|soup| {
+            let mut rng: StdRng = SeedableRng::from_seed(&[config.seed, soup][..]);
+            let mut grid = Grid::new(config.soup_size, config.soup_size);
+            for cell in grid.cells_mut() {
+                *cell = if rng.gen::<f64>() < config.density { 1 } else { 0 };
+            }
+            let (generations, objects) =
+                census_objects(&grid, rule, config.max_generations, config.max_period).unwrap();
+            let mut census = Census::new(rule);
+            census.add(generations, &objects);
+            census
+        })
+        .reduce(|| Census::new(rule), Census::merge);
+    Ok(census)
+}
+
+/// 盤面を安定するまで動かし、物体ごとに分けて種類を調べる
+///
+/// 返り値は(安定するまでにかかった世代数, 物体の一覧)。
+/// `max_generations`世代までに安定しなければ、その時点の状態を調べる
+///
+/// 生きたセルの数が周期的になったら安定したとみなす。
+/// 最後の2周期の間に生きていたセルを縦横斜めにつないだものを物体の候補とし、
+/// 近くにある候補どうしは、互いに影響し合っている(別々に動かすと結果が変わる)ときだけ1つの物体にまとめる
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::life_pattern::Pattern;
+/// use my_alife::algorithm::life_rule::LifeRule;
+/// use my_alife::algorithm::soup_census::{census_objects, ObjectClass};
+///
+/// // ブロック、ブリンカー、グライダー
+/// let pattern = Pattern::from_rle("x = 18, y = 16\n2o$2o5$8b3o7$16bo$17bo$15b3o!").unwrap();
+/// let (_, mut objects) = census_objects(pattern.grid(), LifeRule::conway(), 1000, 64).unwrap();
+/// objects.sort_by(|a, b| a.apgcode.cmp(&b.apgcode));
+/// let names = objects.iter().map(|object| (object.apgcode.as_str(), object.class)).collect::<Vec<_>>();
+/// assert_eq!(
+///     names,
+///     vec![
+///         ("xp2_7", ObjectClass::Oscillator { period: 2 }),
+///         ("xq4_153", ObjectClass::Spaceship { period: 4, dy: 1, dx: 1 }),
+///         ("xs4_33", ObjectClass::StillLife),
+///     ]
+/// );
+/// ```
+pub fn census_objects(
+    grid: &Grid,
+    rule: LifeRule,
+    max_generations: u64,
+    max_period: u64,
+) -> Result<(u64, Vec<CensusObject>), failure::Error> {
+    let mut life = SparseLife::from_grid(grid, rule, 0, 0)?;
+    let window = (4 * max_period) as usize;
+    let mut populations = vec![life.population()];
+    let mut period = None;
+    while life.generation() < max_generations {
+        life.step();
+        populations.push(life.population());
+        if populations.len() > window {
+            period = population_period(&populations[populations.len() - window..], max_period);
+            if period.is_some() {
+                break;
+            }
+        }
+    }
+    let generations = life.generation();
+
+    // 最後の2周期の間に生きていたセルを(縦横斜めに)つないで物体の候補にする
+    let trace_generations = 2 * period.unwrap_or(1);
+    let final_cells = life.cells().clone();
+    let mut trace = final_cells.clone();
+    let mut tracer = life.clone();
+    for _ in 0..trace_generations {
+        tracer.step();
+        trace.extend(tracer.cells().iter().cloned());
+    }
+    let (component_count, component_of) = connected_components(&trace);
+    let mut groups = vec![HashSet::new(); component_count];
+    for (cell, &id) in &component_of {
+        if final_cells.contains(cell) {
+            groups[id].insert(*cell);
+        }
+    }
+
+    // 間の空白が1セルしかない候補どうしは、別々に動かした結果と一緒に動かした結果が違えば同じ物体にする
+    let mut near_pairs = HashSet::new();
+    for (&(i, j), &a) in &component_of {
+        for di in -2..3 {
+            for dj in -2..3 {
+                if let Some(&b) = component_of.get(&(i + di, j + dj)) {
+                    if a < b {
+                        near_pairs.insert((a, b));
+                    }
+                }
+            }
+        }
+    }
+    let mut parents = (0..component_count).collect::<Vec<_>>();
+    let mut merged = true;
+    while merged {
+        merged = false;
+        for &(a, b) in &near_pairs {
+            let (a, b) = (find_root(&mut parents, a), find_root(&mut parents, b));
+            if a != b && interacts(&groups[a], &groups[b], rule, trace_generations) {
+                let cells = groups[b].drain().collect::<Vec<_>>();
+                groups[a].extend(cells);
+                parents[b] = a;
+                merged = true;
+            }
+        }
+    }
+
+    let objects = (0..component_count)
+        .filter(|&k| parents[k] == k && !groups[k].is_empty())
+        .map(|k| {
+            let mut cells = groups[k].iter().cloned().collect::<Vec<_>>();
+            cells.sort();
+            let (apgcode, class) = identify(&cells, rule, max_period);
+            CensusObject {
+                apgcode: apgcode,
+                class: class,
+                cells: cells,
+            }
+        })
+        .collect();
+    Ok((generations, objects))
+}
+
+/// 1つの物体だけを置いた盤面を動かして種類を調べ、(apgcode, 種類)を返す
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::life_rule::LifeRule;
+/// use my_alife::algorithm::soup_census::{identify, ObjectClass};
+///
+/// // 向きや位相が違っても同じapgcodeになる
+/// let glider = [(0, 1), (1, 2), (2, 0), (2, 1), (2, 2)];
+/// let other_glider = [(10, 10), (10, 11), (11, 10), (11, 12), (12, 10)];
+/// let (apgcode, class) = identify(&glider, LifeRule::conway(), 64);
+/// assert_eq!(apgcode, "xq4_153");
+/// assert_eq!(class, ObjectClass::Spaceship { period: 4, dy: 1, dx: 1 });
+/// assert_eq!(identify(&other_glider, LifeRule::conway(), 64).0, "xq4_153");
+/// ```
+pub fn identify(cells: &[(i64, i64)], rule: LifeRule, max_period: u64) -> (String, ObjectClass) {
+    let mut life = match SparseLife::new(rule) {
+        Ok(life) => life,
+        Err(_) => return (unidentified_code(cells), ObjectClass::Unidentified),
+    };
+    for &(i, j) in cells {
+        life.set(i, j, true);
+    }
+    let (initial_shape, initial_offset) = normalize(cells.to_vec());
+    let mut phases = vec![initial_shape.clone()];
+    for period in 1..max_period + 1 {
+        life.step();
+        if life.population() == 0 {
+            break;
+        }
+        let (shape, offset) = normalize(life.cells().iter().cloned().collect());
+        if shape == initial_shape {
+            let (dy, dx) = (offset.0 - initial_offset.0, offset.1 - initial_offset.1);
+            let (prefix, class) = if (dy, dx) != (0, 0) {
+                (
+                    "xq",
+                    ObjectClass::Spaceship {
+                        period: period,
+                        dy: dy,
+                        dx: dx,
+                    },
+                )
+            } else if period == 1 {
+                ("xs", ObjectClass::StillLife)
+            } else {
+                ("xp", ObjectClass::Oscillator { period: period })
+            };
+            let number = if class == ObjectClass::StillLife {
+                cells.len() as u64
+            } else {
+                period
+            };
+            return (format!("{}{}_{}", prefix, number, canonical_code(&phases)), class);
+        }
+        phases.push(shape);
+    }
+    (unidentified_code(cells), ObjectClass::Unidentified)
+}
+
+/// 8つの向き・全ての位相のExtended Wechsler形式のうち、最も短く(同じ長さなら辞書順で最も小さい)ものを選ぶ
+fn canonical_code(phases: &[Vec<(i64, i64)>]) -> String {
+    let mut best: Option<String> = None;
+    for shape in phases {
+        for orientation in 0..8 {
+            let transformed = shape
+                .iter()
+                .map(|&(i, j)| {
+                    let (i, j) = if orientation & 4 != 0 { (j, i) } else { (i, j) };
+                    let i = if orientation & 2 != 0 { -i } else { i };
+                    let j = if orientation & 1 != 0 { -j } else { j };
+                    (i, j)
+                })
+                .collect();
+            let code = wechsler(&normalize(transformed).0);
+            let better = match best {
+                Some(ref best) => (code.len(), &code) < (best.len(), best),
+                None => true,
+            };
+            if better {
+                best = Some(code);
+            }
+        }
+    }
+    best.unwrap_or_default()
+}
+
+/// 左上が`(0, 0)`の形をExtended Wechsler形式にする
+///
+/// 5行ずつの帯に分け、各帯を左の列から順に「5セルを上から1, 2, 4, 8, 16として足した数」の文字で表す。
+/// 帯の間は`z`で区切る。連続する0は`w`(2個)、`x`(3個)、`y0`から`yz`(4個から39個)に縮め、帯の末尾の0は省く
+fn wechsler(shape: &[(i64, i64)]) -> String {
+    let height = shape.iter().map(|&(i, _)| i + 1).max().unwrap_or(0) as usize;
+    let width = shape.iter().map(|&(_, j)| j + 1).max().unwrap_or(0) as usize;
+    let strips = (height + 4) / 5;
+    let mut columns = vec![vec![0usize; width]; strips];
+    for &(i, j) in shape {
+        columns[i as usize / 5][j as usize] |= 1 << (i as usize % 5);
+    }
+    let mut code = String::new();
+    for (strip, values) in columns.iter().enumerate() {
+        if strip > 0 {
+            code.push('z');
+        }
+        let len = values.iter().rposition(|value| *value != 0).map_or(0, |j| j + 1);
+        let mut zeros = 0;
+        for value in &values[..len] {
+            if *value == 0 {
+                zeros += 1;
+                continue;
+            }
+            push_zeros(&mut code, zeros);
+            zeros = 0;
+            code.push(COLUMN_CHARS[*value] as char);
+        }
+    }
+    code
+}
+
+fn push_zeros(code: &mut String, mut zeros: usize) {
+    while zeros > 0 {
+        match zeros {
+            1 => code.push('0'),
+            2 => code.push('w'),
+            3 => code.push('x'),
+            _ => {
+                let run = zeros.min(39);
+                code.push('y');
+                code.push(ZERO_RUN_CHARS[run - 4] as char);
+                zeros -= run;
+                continue;
+            }
+        }
+        break;
+    }
+}
+
+fn unidentified_code(cells: &[(i64, i64)]) -> String {
+    format!("zz_UNIDENTIFIED_{}", cells.len())
+}
+
+/// 左上が`(0, 0)`になるように平行移動した(並べ替え済みの)形と、元の左上の座標
+fn normalize(mut cells: Vec<(i64, i64)>) -> (Vec<(i64, i64)>, (i64, i64)) {
+    let top = cells.iter().map(|&(i, _)| i).min().unwrap_or(0);
+    let left = cells.iter().map(|&(_, j)| j).min().unwrap_or(0);
+    for cell in &mut cells {
+        *cell = (cell.0 - top, cell.1 - left);
+    }
+    cells.sort();
+    (cells, (top, left))
+}
+
+/// 生きたセルの数の列が周期`max_period`以下で繰り返していれば、その最小の周期
+fn population_period(populations: &[usize], max_period: u64) -> Option<u64> {
+    (1..max_period + 1).find(|&period| {
+        let period = period as usize;
+        (period..populations.len()).all(|t| populations[t] == populations[t - period])
+    })
+}
+
+/// 2つの物体を別々に動かした結果と、一緒に動かした結果が`generations`世代以内に食い違えば`true`
+fn interacts(a: &HashSet<(i64, i64)>, b: &HashSet<(i64, i64)>, rule: LifeRule, generations: u64) -> bool {
+    let mut lives = [a, b, &a.union(b).cloned().collect()]
+        .iter()
+        .map(|cells| {
+            let mut life = SparseLife::new(rule).unwrap();
+            for &(i, j) in cells.iter() {
+                life.set(i, j, true);
+            }
+            life
+        })
+        .collect::<Vec<_>>();
+    for _ in 0..generations {
+        for life in &mut lives {
+            life.step();
+        }
+        let separate = lives[0]
+            .cells()
+            .union(lives[1].cells())
+            .cloned()
+            .collect::<HashSet<_>>();
+        if &separate != lives[2].cells() {
+            return true;
+        }
+    }
+    false
+}
+
+fn find_root(parents: &mut [usize], k: usize) -> usize {
+    let mut root = k;
+    while parents[root] != root {
+        root = parents[root];
+    }
+    parents[k] = root;
+    root
+}
+
+/// 縦横斜めに隣り合うセルをつないだ連結成分の数と、各セルが属する連結成分の番号
+fn connected_components(cells: &HashSet<(i64, i64)>) -> (usize, HashMap<(i64, i64), usize>) {
+    let mut component_of: HashMap<(i64, i64), usize> = HashMap::new();
+    let mut count = 0;
+    for &start in cells {
+        if component_of.contains_key(&start) {
+            continue;
+        }
+        let id = count;
+        count += 1;
+        let mut stack = vec![start];
+        component_of.insert(start, id);
+        while let Some((i, j)) = stack.pop() {
+            for &(di, dj) in NEIGHBOR_OFFSETS.iter() {
+                let neighbor = (i + di, j + dj);
+                if cells.contains(&neighbor) && !component_of.contains_key(&neighbor) {
+                    component_of.insert(neighbor, id);
+                    stack.push(neighbor);
+                }
+            }
+        }
+    }
+    (count, component_of)
+}
@@ -0,0 +1,238 @@
+use algorithm::grid::Grid;
+use algorithm::life_pattern::Pattern;
+use algorithm::life_rule::LifeRule;
+use failure;
+use std::collections::{HashMap, HashSet};
+
+/// ムーア近傍(周囲8セル)の相対座標
+const NEIGHBOR_OFFSETS: [(i64, i64); 8] = [(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)];
+
+/// 生きているセルの座標だけを持つ、端のない盤面のライフゲーム
+///
+/// `game_of_life`のように周期境界で折り返さないので、グライダーはどこまでも進んでいく。
+/// 座標`(i, j)`は`i`行`j`列で、負の値も使える。
+/// 1世代の計算は生きているセルの数に比例する時間で済むので、広い範囲にまばらにセルがある場合に向いている
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::life_pattern::Pattern;
+/// use my_alife::algorithm::sparse_life::{SparseLife, Viewport};
+///
+/// let glider = Pattern::from_rle("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+/// let mut life = SparseLife::from_pattern(&glider, 0, 0).unwrap();
+/// for _ in 0..400 {
+///     life.step();
+/// }
+/// // 折り返さずに右下へ100セル進んでいる
+/// assert_eq!(life.bounding_box(), Some((100, 100, 3, 3)));
+///
+/// let mut viewport = Viewport::new(-5, -5, 10, 10);
+/// assert_eq!(life.to_grid(&viewport).population(), 0);
+/// viewport.follow(&life);
+/// assert_eq!(viewport.top, 96);
+/// assert_eq!(life.to_grid(&viewport).population(), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SparseLife {
+    cells: HashSet<(i64, i64)>,
+    rule: LifeRule,
+    generation: u64,
+}
+
+impl SparseLife {
+    /// 全てのセルが死んでいるSparseLifeインスタンスを生成する
+    ///
+    /// 空白から誕生する(B0を含む)ルールは、無限に広い盤面の全てが生きたセルになってしまうので使えない
+    ///
+    /// # Arguments
+    /// * `rule` - B/S記法のルール
+    pub fn new(rule: LifeRule) -> Result<SparseLife, failure::Error> {
+        if rule.is_born(0) {
+            return Err(failure::err_msg(format!(
+                "an unbounded universe does not support B0 rules ({})",
+                rule
+            )));
+        }
+        Ok(SparseLife {
+            cells: HashSet::new(),
+            rule: rule,
+            generation: 0,
+        })
+    }
+
+    /// Gridの左上のセルが座標`(top, left)`に来るように置いたSparseLifeインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `grid` - 初期状態
+    /// * `rule` - B/S記法のルール
+    /// * `top` - `grid`の上端の行
+    /// * `left` - `grid`の左端の列
+    pub fn from_grid(grid: &Grid, rule: LifeRule, top: i64, left: i64) -> Result<SparseLife, failure::Error> {
+        let mut life = SparseLife::new(rule)?;
+        for i in 0..grid.height() {
+            for j in 0..grid.width() {
+                if grid[(i, j)] != 0 {
+                    life.set(top + i as i64, left + j as i64, true);
+                }
+            }
+        }
+        Ok(life)
+    }
+
+    /// パターンの左上が座標`(top, left)`に来るように置いたSparseLifeインスタンスを生成する
+    ///
+    /// パターンにルールが書かれていればそのルール、なければB3/S23を使う
+    pub fn from_pattern(pattern: &Pattern, top: i64, left: i64) -> Result<SparseLife, failure::Error> {
+        let rule = pattern.rule().unwrap_or_else(LifeRule::conway);
+        Self::from_grid(pattern.grid(), rule, top, left)
+    }
+
+    /// ルール
+    pub fn rule(&self) -> LifeRule {
+        self.rule
+    }
+
+    /// 今の世代
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// 生きているセルの数
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// 生きているセルの座標
+    pub fn cells(&self) -> &HashSet<(i64, i64)> {
+        &self.cells
+    }
+
+    /// セル`(i, j)`の状態(0が死、1が生)
+    pub fn get(&self, i: i64, j: i64) -> u8 {
+        self.cells.contains(&(i, j)) as u8
+    }
+
+    /// セル`(i, j)`の状態を変える
+    pub fn set(&mut self, i: i64, j: i64, alive: bool) {
+        if alive {
+            self.cells.insert((i, j));
+        } else {
+            self.cells.remove(&(i, j));
+        }
+    }
+
+    /// 1世代進める
+    ///
+    /// 生きているセルの周囲8セルに1ずつ数を配り、配られた数(生きている近傍の数)から次の状態を決める。
+    /// 何も配られなかったセルは近傍が全て死んでいるので、S0のルールでなければ次も死んでいる
+    pub fn step(&mut self) {
+        let mut neighbor_cell_sums: HashMap<(i64, i64), u8> = HashMap::with_capacity(self.cells.len() * 8);
+        for &(i, j) in &self.cells {
+            for &(di, dj) in NEIGHBOR_OFFSETS.iter() {
+                *neighbor_cell_sums.entry((i + di, j + dj)).or_insert(0) += 1;
+            }
+        }
+        let mut next_cells = neighbor_cell_sums
+            .iter()
+            .filter(|&(position, &neighbor_cell_sum)| {
+                let cell = self.cells.contains(position) as u8;
+                self.rule.next_state(cell, neighbor_cell_sum) == 1
+            })
+            .map(|(position, _)| *position)
+            .collect::<HashSet<_>>();
+        if self.rule.survives(0) {
+            for position in &self.cells {
+                if !neighbor_cell_sums.contains_key(position) {
+                    next_cells.insert(*position);
+                }
+            }
+        }
+        self.cells = next_cells;
+        self.generation += 1;
+    }
+
+    /// 生きているセルを全て含む最小の長方形`(top, left, height, width)`。生きているセルがなければ`None`
+    pub fn bounding_box(&self) -> Option<(i64, i64, usize, usize)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let top = self.cells.iter().map(|&(i, _)| i).min().unwrap();
+        let bottom = self.cells.iter().map(|&(i, _)| i).max().unwrap();
+        let left = self.cells.iter().map(|&(_, j)| j).min().unwrap();
+        let right = self.cells.iter().map(|&(_, j)| j).max().unwrap();
+        Some((top, left, (bottom - top + 1) as usize, (right - left + 1) as usize))
+    }
+
+    /// `viewport`の範囲をGridに書き出す
+    pub fn to_grid(&self, viewport: &Viewport) -> Grid {
+        let mut grid = Grid::new(viewport.height, viewport.width);
+        for &(i, j) in &self.cells {
+            if let Some(position) = viewport.to_local(i, j) {
+                grid[position] = 1;
+            }
+        }
+        grid
+    }
+}
+
+/// 端のない盤面のうち、表示する長方形の範囲
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Viewport {
+    /// 上端の行
+    pub top: i64,
+    /// 左端の列
+    pub left: i64,
+    /// 縦のセルの数
+    pub height: usize,
+    /// 横のセルの数
+    pub width: usize,
+}
+
+impl Viewport {
+    /// Viewportインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `top` - 上端の行
+    /// * `left` - 左端の列
+    /// * `height` - 縦のセルの数
+    /// * `width` - 横のセルの数
+    pub fn new(top: i64, left: i64, height: usize, width: usize) -> Viewport {
+        Viewport {
+            top: top,
+            left: left,
+            height: height,
+            width: width,
+        }
+    }
+
+    /// 中心が`(i, j)`になるように動かす
+    pub fn center_on(&mut self, i: i64, j: i64) {
+        self.top = i - self.height as i64 / 2;
+        self.left = j - self.width as i64 / 2;
+    }
+
+    /// `(di, dj)`だけ動かす
+    pub fn pan(&mut self, di: i64, dj: i64) {
+        self.top += di;
+        self.left += dj;
+    }
+
+    /// 生きているセルを囲む長方形の中心に動かす。生きているセルがなければ動かさない
+    pub fn follow(&mut self, life: &SparseLife) {
+        if let Some((top, left, height, width)) = life.bounding_box() {
+            self.center_on(top + height as i64 / 2, left + width as i64 / 2);
+        }
+    }
+
+    /// 盤面の座標`(i, j)`をviewport内の座標に直す。範囲外なら`None`
+    pub fn to_local(&self, i: i64, j: i64) -> Option<(usize, usize)> {
+        let (y, x) = (i - self.top, j - self.left);
+        if 0 <= y && y < self.height as i64 && 0 <= x && x < self.width as i64 {
+            Some((y as usize, x as usize))
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use algorithm::generalized_cellular_automata::CellularAutomataRule;
+use ndarray::Array1;
+use rand::{Rng, SeedableRng, StdRng};
+use std::mem;
+
+/// 1次元セルラーオートマトンの更新方法
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UpdateScheme {
+    /// 全セルを同時に更新する(`cellular_automata`と同じ)
+    Synchronous,
+    /// 1フェーズで全セルをランダムな順番に1つずつ更新する。先に更新されたセルの新しい状態が後のセルの近傍になる
+    RandomSequential,
+    /// 各セルが確率`alpha`で更新され、残りは今の状態のまま(α-非同期更新)
+    Asynchronous(f64),
+}
+
+/// 非同期・確率的に1次元セルラーオートマトンを更新する構造体
+///
+/// 乱数はseedから作るので、同じseedなら同じ結果が再現できる。
+/// `noise`が0より大きい場合、各セルの遷移結果は確率`noise`で別の状態に反転する
+/// (2状態なら0と1が入れ替わり、3状態以上なら他の状態からランダムに選ぶ)
+///
+/// # Example
+/// ```
+/// extern crate ndarray;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::stochastic_cellular_automata::{StochasticUpdater, UpdateScheme};
+/// use ndarray::Array1;
+///
+/// let initial = Array1::from_vec((0..100).map(|i| (i % 3 % 2) as u32).collect());
+/// let run = |seed| {
+///     let mut updater = StochasticUpdater::new(110u8, UpdateScheme::Asynchronous(0.5), 0.01, seed);
+///     let mut state = (initial.clone(), Array1::<u32>::zeros(100));
+///     for _ in 0..50 {
+///         updater.update(&mut state);
+///     }
+///     state.0
+/// };
+/// // 同じseedなら同じ結果になる
+/// assert_eq!(run(1), run(1));
+/// ```
+pub struct StochasticUpdater<R> {
+    rule: R,
+    scheme: UpdateScheme,
+    noise: f64,
+    rng: StdRng,
+}
+
+impl<R> StochasticUpdater<R>
+where
+    R: CellularAutomataRule,
+{
+    /// StochasticUpdaterインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `rule` - 状態を更新するルール
+    /// * `scheme` - 更新方法
+    /// * `noise` - 遷移結果が反転する確率ε
+    /// * `seed` - 乱数のseed
+    pub fn new(rule: R, scheme: UpdateScheme, noise: f64, seed: usize) -> StochasticUpdater<R> {
+        StochasticUpdater {
+            rule: rule,
+            scheme: scheme,
+            noise: noise,
+            rng: SeedableRng::from_seed(&[seed][..]),
+        }
+    }
+
+    /// フェーズ1回分の計算を行う
+    ///
+    /// 境界条件は周期境界。`cellular_automata`と同じく計算結果は`state.0`に入る
+    pub fn update(&mut self, state: &mut (Array1<u32>, Array1<u32>)) {
+        match self.scheme {
+            UpdateScheme::Synchronous => self.update_synchronously(state, 1.0),
+            UpdateScheme::Asynchronous(alpha) => self.update_synchronously(state, alpha),
+            UpdateScheme::RandomSequential => self.update_sequentially(&mut state.0),
+        }
+    }
+
+    /// 全セルの次の状態を今の状態から計算し、各セルは確率`alpha`でその状態になる
+    fn update_synchronously(&mut self, state: &mut (Array1<u32>, Array1<u32>), alpha: f64) {
+        {
+            let current_state = &state.0;
+            let next_state = &mut state.1;
+            let space_size = current_state.len();
+            for i in 0..space_size {
+                next_state[i] = if alpha >= 1.0 || self.rng.gen::<f64>() < alpha {
+                    let cell = self.next_cell(current_state, i);
+                    self.add_noise(cell)
+                } else {
+                    current_state[i]
+                };
+            }
+        }
+        mem::swap(&mut state.0, &mut state.1);
+    }
+
+    /// ランダムな順番でセルを1つずつその場で更新する
+    fn update_sequentially(&mut self, state: &mut Array1<u32>) {
+        let mut order = (0..state.len()).collect::<Vec<_>>();
+        self.rng.shuffle(&mut order);
+        for i in order {
+            let cell = self.next_cell(state, i);
+            state[i] = self.add_noise(cell);
+        }
+    }
+
+    fn next_cell(&self, state: &Array1<u32>, i: usize) -> u32 {
+        let space_size = state.len();
+        let radius = self.rule.radius();
+        let neighborhood = (0..2 * radius + 1)
+            .map(|j| state[(i + space_size * radius + j - radius) % space_size])
+            .collect::<Vec<_>>();
+        self.rule.next_state(&neighborhood)
+    }
+
+    fn add_noise(&mut self, cell: u32) -> u32 {
+        if self.noise <= 0.0 || self.rng.gen::<f64>() >= self.noise {
+            return cell;
+        }
+        let states = self.rule.states();
+        // 今の状態以外からランダムに選ぶ
+        (cell + self.rng.gen_range(1, states)) % states
+    }
+}
@@ -0,0 +1,179 @@
+use algorithm::generalized_cellular_automata::CellularAutomataRule;
+use failure;
+use num::{BigUint, Integer, ToPrimitive, Zero};
+use std::str::FromStr;
+
+/// 総和型(totalistic)・外総和型(outer totalistic)の1次元セルラーオートマトンのルール
+///
+/// * 総和型: 次の状態は近傍(自分自身を含む`2 * radius + 1`個)の状態の総和`s`だけで決まる。
+///   コード番号を`states`進数で表したときの`s`桁目(1の位が0桁目)が次の状態になる
+/// * 外総和型: 次の状態は自分自身の状態`c`と、自分以外の近傍(`2 * radius`個)の総和`s`で決まる。
+///   コード番号の`s * states + c`桁目が次の状態になる
+///
+/// 文字列からは`"code 912, k=3, r=1"`のようなウルフラムの記法でparseできる。
+/// 先頭に`outer`をつけると外総和型になる(`"outer code 52, k=2, r=1"`)。`k`と`r`を省略した場合は2と1になる
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::generalized_cellular_automata::CellularAutomataRule;
+/// use my_alife::algorithm::totalistic_cellular_automata::TotalisticRule;
+///
+/// let rule = "code 912, k=3, r=1".parse::<TotalisticRule>().unwrap();
+/// assert_eq!(rule.table(), &[0, 1, 2, 0, 2, 0, 1]);
+/// // 総和が2なので次の状態は2
+/// assert_eq!(rule.next_state(&[1, 0, 1]), 2);
+/// assert!("code 2187, k=3, r=1".parse::<TotalisticRule>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TotalisticRule {
+    radius: usize,
+    states: u32,
+    outer: bool,
+    table: Vec<u32>,
+}
+
+impl TotalisticRule {
+    /// 総和型のルールを生成する
+    ///
+    /// # Arguments
+    /// * `code` - コード番号
+    /// * `states` - セルの状態数(2以上)
+    /// * `radius` - 近傍半径(1以上)
+    pub fn totalistic(code: &BigUint, states: u32, radius: usize) -> Result<TotalisticRule, failure::Error> {
+        Self::from_code(code, states, radius, false)
+    }
+
+    /// 外総和型のルールを生成する
+    ///
+    /// # Arguments
+    /// * `code` - コード番号
+    /// * `states` - セルの状態数(2以上)
+    /// * `radius` - 近傍半径(1以上)
+    pub fn outer_totalistic(code: &BigUint, states: u32, radius: usize) -> Result<TotalisticRule, failure::Error> {
+        Self::from_code(code, states, radius, true)
+    }
+
+    /// 外総和型かどうか
+    pub fn is_outer(&self) -> bool {
+        self.outer
+    }
+
+    /// 総和(外総和型の場合は`s * states + c`)ごとの次の状態
+    pub fn table(&self) -> &[u32] {
+        &self.table
+    }
+
+    /// コード番号
+    pub fn code(&self) -> BigUint {
+        let base = BigUint::from(self.states);
+        self.table
+            .iter()
+            .rev()
+            .fold(BigUint::zero(), |acc, e| acc * &base + BigUint::from(*e))
+    }
+
+    fn from_code(code: &BigUint, states: u32, radius: usize, outer: bool) -> Result<TotalisticRule, failure::Error> {
+        if radius < 1 {
+            return Err(failure::err_msg("radius must be 1 or more"));
+        }
+        if states < 2 {
+            return Err(failure::err_msg("states must be 2 or more"));
+        }
+        let size = if outer {
+            (2 * radius * (states as usize - 1) + 1) * states as usize
+        } else {
+            (2 * radius + 1) * (states as usize - 1) + 1
+        };
+        let base = BigUint::from(states);
+        let mut rest = code.clone();
+        let mut table = Vec::with_capacity(size);
+        for _ in 0..size {
+            let (quotient, digit) = rest.div_rem(&base);
+            table.push(digit.to_u32().unwrap());
+            rest = quotient;
+        }
+        if !rest.is_zero() {
+            return Err(failure::err_msg(format!(
+                "code {} is too large for k={}, r={}",
+                code, states, radius
+            )));
+        }
+        Ok(TotalisticRule {
+            radius: radius,
+            states: states,
+            outer: outer,
+            table: table,
+        })
+    }
+}
+
+impl CellularAutomataRule for TotalisticRule {
+    fn radius(&self) -> usize {
+        self.radius
+    }
+
+    fn states(&self) -> u32 {
+        self.states
+    }
+
+    fn next_state(&self, neighborhood: &[u32]) -> u32 {
+        let sum: u32 = neighborhood.iter().sum();
+        if self.outer {
+            let center = neighborhood[self.radius];
+            self.table[((sum - center) * self.states + center) as usize]
+        } else {
+            self.table[sum as usize]
+        }
+    }
+}
+
+impl FromStr for TotalisticRule {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<TotalisticRule, failure::Error> {
+        let lower = s.to_lowercase();
+        let tokens = lower
+            .split(|c: char| c.is_whitespace() || c == ',' || c == '=' || c == '(' || c == ')')
+            .filter(|token| !token.is_empty())
+            .collect::<Vec<_>>();
+        let mut tokens = tokens.iter().peekable();
+        let outer = if tokens.peek() == Some(&&"outer") {
+            tokens.next();
+            true
+        } else {
+            false
+        };
+        if tokens.next() != Some(&"code") {
+            return Err(failure::err_msg(format!("\"{}\" does not start with \"code\"", s)));
+        }
+        let code = match tokens.next() {
+            Some(code) => code
+                .parse::<BigUint>()
+                .map_err(|_| failure::err_msg(format!("invalid code number \"{}\"", code)))?,
+            None => return Err(failure::err_msg(format!("\"{}\" has no code number", s))),
+        };
+        let mut states = 2;
+        let mut radius = 1;
+        while let Some(key) = tokens.next() {
+            let value = tokens
+                .next()
+                .ok_or_else(|| failure::err_msg(format!("\"{}\" has no value", key)))?;
+            match *key {
+                "k" => {
+                    states = value
+                        .parse()
+                        .map_err(|_| failure::err_msg(format!("invalid k \"{}\"", value)))?
+                }
+                "r" => {
+                    radius = value
+                        .parse()
+                        .map_err(|_| failure::err_msg(format!("invalid r \"{}\"", value)))?
+                }
+                _ => return Err(failure::err_msg(format!("unknown parameter \"{}\"", key))),
+            }
+        }
+        Self::from_code(&code, states, radius, outer)
+    }
+}
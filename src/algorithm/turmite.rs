@@ -0,0 +1,557 @@
+use algorithm::boundary::Boundary;
+use algorithm::grid::Grid;
+use algorithm::sparse_life::Viewport;
+use failure;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// ハイウェイとみなすために同じ動きが続かなければならない周期の数
+const HIGHWAY_REPEATS: usize = 10;
+
+/// ターマイトの向き
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    /// `turn`だけ向きを変える
+    pub fn turn(self, turn: Turn) -> Direction {
+        const CLOCKWISE: [Direction; 4] = [Direction::North, Direction::East, Direction::South, Direction::West];
+        let quarters = match turn {
+            Turn::NoTurn => 0,
+            Turn::Right => 1,
+            Turn::UTurn => 2,
+            Turn::Left => 3,
+        };
+        CLOCKWISE[(self as usize + quarters) % 4]
+    }
+
+    /// 1歩進んだときの`(行, 列)`の変化
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Direction::North => (-1, 0),
+            Direction::East => (0, 1),
+            Direction::South => (1, 0),
+            Direction::West => (0, -1),
+        }
+    }
+}
+
+/// ターマイトの曲がり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Turn {
+    /// 曲がらない(`N`)
+    NoTurn,
+    /// 右に90度(`R`)
+    Right,
+    /// 180度(`U`)
+    UTurn,
+    /// 左に90度(`L`)
+    Left,
+}
+
+impl Turn {
+    fn from_letter(c: char) -> Option<Turn> {
+        match c {
+            'N' => Some(Turn::NoTurn),
+            'R' => Some(Turn::Right),
+            'U' => Some(Turn::UTurn),
+            'L' => Some(Turn::Left),
+            _ => None,
+        }
+    }
+
+    fn letter(self) -> char {
+        match self {
+            Turn::NoTurn => 'N',
+            Turn::Right => 'R',
+            Turn::UTurn => 'U',
+            Turn::Left => 'L',
+        }
+    }
+
+    /// Gollyの記法の数(1、2、4、8)
+    fn from_golly(n: usize) -> Option<Turn> {
+        match n {
+            1 => Some(Turn::NoTurn),
+            2 => Some(Turn::Right),
+            4 => Some(Turn::UTurn),
+            8 => Some(Turn::Left),
+            _ => None,
+        }
+    }
+
+    fn golly(self) -> usize {
+        match self {
+            Turn::NoTurn => 1,
+            Turn::Right => 2,
+            Turn::UTurn => 4,
+            Turn::Left => 8,
+        }
+    }
+}
+
+/// ターマイトが今の状態と足元のセルの色から決める動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Transition {
+    /// 足元のセルに書く色
+    pub write: u8,
+    /// 曲がり方
+    pub turn: Turn,
+    /// 次の状態
+    pub next_state: usize,
+}
+
+/// ターマイトのルール
+///
+/// 状態`state`のターマイトが色`color`のセルにいるとき、`transition(state, color)`に従って
+/// セルの色を塗り替え、向きを変えてから1歩進む。文字列からは次の2つの形でparseできる
+/// * `"RL"`や`"LLRR"` - 状態が1つのラングトンのアリ型のルール。色`k`のセルでは`k`文字目の向きに曲がり、
+///   セルの色を`k + 1`(最後の色なら0)にする。`L`、`R`、`N`(曲がらない)、`U`(180度)が使える
+/// * `"{{{1, 2, 0}, {0, 8, 0}}}"` - [Golly](http://golly.sourceforge.net/)の記法。
+///   状態ごと・色ごとに`{書く色, 曲がり方, 次の状態}`を並べる。曲がり方は1が曲がらない、2が右、4が180度、8が左
+///
+/// # Example
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::turmite::{Turn, TurmiteRule};
+///
+/// let ant = "RL".parse::<TurmiteRule>().unwrap();
+/// assert_eq!(ant, TurmiteRule::langtons_ant());
+/// assert_eq!("{{{1, 2, 0}, {0, 8, 0}}}".parse::<TurmiteRule>().unwrap(), ant);
+/// assert_eq!(ant.transition(0, 1).turn, Turn::Left);
+/// assert_eq!("LLRR".parse::<TurmiteRule>().unwrap().colors(), 4);
+///
+/// // 2状態のターマイト(Fibonacci spiral)
+/// let spiral = "{{{1, 8, 1}, {1, 8, 1}}, {{1, 2, 1}, {0, 1, 0}}}".parse::<TurmiteRule>().unwrap();
+/// assert_eq!((spiral.states(), spiral.colors()), (2, 2));
+/// assert_eq!(spiral.to_string(), "{{{1, 8, 1}, {1, 8, 1}}, {{1, 2, 1}, {0, 1, 0}}}");
+/// assert!("RX".parse::<TurmiteRule>().is_err());
+/// assert!("{{{2, 2, 0}, {0, 8, 0}}}".parse::<TurmiteRule>().is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TurmiteRule {
+    colors: u8,
+    states: usize,
+    transitions: Vec<Transition>,
+}
+
+impl TurmiteRule {
+    /// TurmiteRuleインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `colors` - セルの色の数(2以上)
+    /// * `transitions` - 状態`state`、色`color`のときの動作を`transitions[state][color]`に入れる
+    pub fn new(colors: u8, transitions: Vec<Vec<Transition>>) -> Result<TurmiteRule, failure::Error> {
+        let states = transitions.len();
+        if colors < 2 || states == 0 {
+            return Err(failure::err_msg(format!(
+                "a turmite needs at least 2 colors and 1 state, not {} colors and {} states",
+                colors, states
+            )));
+        }
+        for (state, row) in transitions.iter().enumerate() {
+            if row.len() != colors as usize {
+                return Err(failure::err_msg(format!(
+                    "state {} has {} transitions for {} colors",
+                    state,
+                    row.len(),
+                    colors
+                )));
+            }
+            for transition in row {
+                if transition.write >= colors || transition.next_state >= states {
+                    return Err(failure::err_msg(format!(
+                        "{:?} in state {} refers to a color or a state that does not exist",
+                        transition, state
+                    )));
+                }
+            }
+        }
+        Ok(TurmiteRule {
+            colors: colors,
+            states: states,
+            transitions: transitions.into_iter().flat_map(|row| row.into_iter()).collect(),
+        })
+    }
+
+    /// ラングトンのアリ(RL)
+    pub fn langtons_ant() -> TurmiteRule {
+        "RL".parse::<TurmiteRule>().unwrap()
+    }
+
+    /// セルの色の数
+    pub fn colors(&self) -> u8 {
+        self.colors
+    }
+
+    /// ターマイトの状態の数
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    /// 状態`state`のターマイトが色`color`のセルにいるときの動作
+    pub fn transition(&self, state: usize, color: u8) -> Transition {
+        self.transitions[state * self.colors as usize + color as usize]
+    }
+
+    /// 状態が1つで、色`k`のセルを`k + 1`に塗り替えるアリ型のルールならその文字列(`"RL"`など)
+    fn ant_letters(&self) -> Option<String> {
+        if self.states != 1 {
+            return None;
+        }
+        let mut letters = String::new();
+        for color in 0..self.colors {
+            let transition = self.transition(0, color);
+            if transition.write != (color + 1) % self.colors {
+                return None;
+            }
+            letters.push(transition.turn.letter());
+        }
+        Some(letters)
+    }
+}
+
+impl FromStr for TurmiteRule {
+    type Err = failure::Error;
+
+    fn from_str(s: &str) -> Result<TurmiteRule, failure::Error> {
+        let s = s.trim();
+        if s.starts_with('{') {
+            return parse_golly_turmite(s);
+        }
+        let turns = s
+            .to_uppercase()
+            .chars()
+            .map(|c| Turn::from_letter(c).ok_or_else(|| failure::err_msg(format!("invalid turn '{}' in \"{}\"", c, s))))
+            .collect::<Result<Vec<_>, _>>()?;
+        if turns.len() > 255 {
+            return Err(failure::err_msg(format!("\"{}\" has too many colors", s)));
+        }
+        let colors = turns.len() as u8;
+        let transitions = turns
+            .iter()
+            .enumerate()
+            .map(|(color, &turn)| Transition {
+                write: ((color + 1) % turns.len()) as u8,
+                turn: turn,
+                next_state: 0,
+            })
+            .collect();
+        TurmiteRule::new(colors, vec![transitions])
+    }
+}
+
+impl fmt::Display for TurmiteRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(letters) = self.ant_letters() {
+            return write!(f, "{}", letters);
+        }
+        let states = (0..self.states)
+            .map(|state| {
+                let transitions = (0..self.colors)
+                    .map(|color| {
+                        let transition = self.transition(state, color);
+                        format!(
+                            "{{{}, {}, {}}}",
+                            transition.write,
+                            transition.turn.golly(),
+                            transition.next_state
+                        )
+                    })
+                    .collect::<Vec<_>>();
+                format!("{{{}}}", transitions.join(", "))
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{{{}}}", states.join(", "))
+    }
+}
+
+/// Gollyの記法`{{{1, 2, 0}, {0, 8, 0}}}`をparseする
+fn parse_golly_turmite(s: &str) -> Result<TurmiteRule, failure::Error> {
+    let invalid = || {
+        failure::err_msg(format!(
+            "\"{}\" is not in \"{{{{{{write, turn, state}}, ...}}, ...}}\" form",
+            s
+        ))
+    };
+    // 一番外側の括弧を外し、状態ごとの`{{...}, {...}}`に分ける
+    let inner = s
+        .trim_start_matches('{')
+        .trim_end_matches('}')
+        .split("}}")
+        .map(|state| state.trim_start_matches(|c: char| c == ',' || c == '{' || c.is_whitespace()))
+        .filter(|state| !state.is_empty())
+        .collect::<Vec<_>>();
+    let mut transitions = Vec::new();
+    for state in inner {
+        let mut row = Vec::new();
+        for triple in state.split('}') {
+            let numbers = triple
+                .trim_start_matches(|c: char| c == ',' || c == '{' || c.is_whitespace())
+                .split(',')
+                .map(|n| n.trim().parse::<usize>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| invalid())?;
+            if numbers.len() != 3 {
+                return Err(invalid());
+            }
+            row.push(Transition {
+                write: numbers[0] as u8,
+                turn: Turn::from_golly(numbers[1])
+                    .ok_or_else(|| failure::err_msg(format!("invalid turn {} in \"{}\"", numbers[1], s)))?,
+                next_state: numbers[2],
+            });
+        }
+        transitions.push(row);
+    }
+    let colors = transitions.first().map_or(0, |row| row.len());
+    TurmiteRule::new(colors as u8, transitions)
+}
+
+/// ターマイトが動き回る盤面
+pub trait TurmiteWorld {
+    /// セル`(i, j)`の色
+    fn color(&self, i: i64, j: i64) -> u8;
+    /// セル`(i, j)`の色を変える
+    fn set_color(&mut self, i: i64, j: i64, color: u8);
+    /// 座標`(i, j)`を盤面の中の座標に写す
+    fn wrap(&self, i: i64, j: i64) -> (i64, i64);
+}
+
+/// ライフゲームと同じ周期境界(トーラス)の盤面
+impl TurmiteWorld for Grid {
+    fn color(&self, i: i64, j: i64) -> u8 {
+        let (i, j) = self.wrap(i, j);
+        self[(i as usize, j as usize)]
+    }
+
+    fn set_color(&mut self, i: i64, j: i64, color: u8) {
+        let (i, j) = self.wrap(i, j);
+        self[(i as usize, j as usize)] = color;
+    }
+
+    fn wrap(&self, i: i64, j: i64) -> (i64, i64) {
+        (
+            Boundary::Periodic.index(i as isize, self.height()).unwrap() as i64,
+            Boundary::Periodic.index(j as isize, self.width()).unwrap() as i64,
+        )
+    }
+}
+
+/// 色0以外のセルだけを持つ、端のない盤面
+///
+/// `SparseLife`と同じく座標に負の値も使え、`Viewport`の範囲をGridに書き出して表示する
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnboundedGrid {
+    cells: HashMap<(i64, i64), u8>,
+}
+
+impl UnboundedGrid {
+    /// 全てのセルが色0のUnboundedGridインスタンスを生成する
+    pub fn new() -> UnboundedGrid {
+        UnboundedGrid { cells: HashMap::new() }
+    }
+
+    /// 色0以外のセルの数
+    pub fn population(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// 色0以外のセルを全て含む最小の長方形`(top, left, height, width)`。そのようなセルがなければ`None`
+    pub fn bounding_box(&self) -> Option<(i64, i64, usize, usize)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        let top = self.cells.keys().map(|&(i, _)| i).min().unwrap();
+        let bottom = self.cells.keys().map(|&(i, _)| i).max().unwrap();
+        let left = self.cells.keys().map(|&(_, j)| j).min().unwrap();
+        let right = self.cells.keys().map(|&(_, j)| j).max().unwrap();
+        Some((top, left, (bottom - top + 1) as usize, (right - left + 1) as usize))
+    }
+
+    /// `viewport`の範囲をGridに書き出す
+    pub fn to_grid(&self, viewport: &Viewport) -> Grid {
+        let mut grid = Grid::new(viewport.height, viewport.width);
+        for (&(i, j), &color) in &self.cells {
+            if let Some(position) = viewport.to_local(i, j) {
+                grid[position] = color;
+            }
+        }
+        grid
+    }
+}
+
+impl TurmiteWorld for UnboundedGrid {
+    fn color(&self, i: i64, j: i64) -> u8 {
+        self.cells.get(&(i, j)).cloned().unwrap_or(0)
+    }
+
+    fn set_color(&mut self, i: i64, j: i64, color: u8) {
+        if color == 0 {
+            self.cells.remove(&(i, j));
+        } else {
+            self.cells.insert((i, j), color);
+        }
+    }
+
+    fn wrap(&self, i: i64, j: i64) -> (i64, i64) {
+        (i, j)
+    }
+}
+
+/// ターマイトが「ハイウェイ」を作り始めたことを表す
+///
+/// ハイウェイは、ターマイトが同じ動きを周期的に繰り返しながら一方向に進み続けてできる模様
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Highway {
+    /// 周期的な動きが始まったステップ数
+    pub start: u64,
+    /// 動きの周期
+    pub period: usize,
+    /// 1周期で進む`(行, 列)`
+    pub displacement: (i64, i64),
+}
+
+/// 2次元のチューリングマシン(ターマイト)
+///
+/// # Example
+/// 何もない盤面から始めたラングトンのアリは、約1万ステップの混沌とした動きの後に、
+/// 104ステップごとに斜めに2セルずつ進むハイウェイを作り始める
+/// ```
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::turmite::{Direction, Turmite, TurmiteRule, UnboundedGrid};
+///
+/// let mut ant = Turmite::new(TurmiteRule::langtons_ant(), 0, 0, Direction::North);
+/// let mut world = UnboundedGrid::new();
+/// let highway = ant.run_until_highway(&mut world, 20000, 200).unwrap();
+/// assert_eq!(highway.start, 9977);
+/// assert_eq!(highway.period, 104);
+/// assert_eq!((highway.displacement.0.abs(), highway.displacement.1.abs()), (2, 2));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Turmite {
+    rule: TurmiteRule,
+    position: (i64, i64),
+    direction: Direction,
+    state: usize,
+    steps: u64,
+}
+
+impl Turmite {
+    /// 状態0のTurmiteインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `rule` - ルール
+    /// * `i` - 最初にいる行
+    /// * `j` - 最初にいる列
+    /// * `direction` - 最初の向き
+    pub fn new(rule: TurmiteRule, i: i64, j: i64, direction: Direction) -> Turmite {
+        Turmite {
+            rule: rule,
+            position: (i, j),
+            direction: direction,
+            state: 0,
+            steps: 0,
+        }
+    }
+
+    /// ルール
+    pub fn rule(&self) -> &TurmiteRule {
+        &self.rule
+    }
+
+    /// 今いるセルの座標`(i, j)`
+    pub fn position(&self) -> (i64, i64) {
+        self.position
+    }
+
+    /// 今の向き
+    pub fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// 今の状態
+    pub fn state(&self) -> usize {
+        self.state
+    }
+
+    /// これまでに進んだステップ数
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    /// 1ステップ進め、足元にあったセルの色を返す
+    ///
+    /// ルールの色の数以上の色のセルは、色の数で割った余りの色とみなす
+    pub fn step<W: TurmiteWorld>(&mut self, world: &mut W) -> u8 {
+        let (i, j) = world.wrap(self.position.0, self.position.1);
+        let color = world.color(i, j) % self.rule.colors();
+        let transition = self.rule.transition(self.state, color);
+        world.set_color(i, j, transition.write);
+        self.direction = self.direction.turn(transition.turn);
+        self.state = transition.next_state;
+        let (di, dj) = self.direction.offset();
+        self.position = world.wrap(i + di, j + dj);
+        self.steps += 1;
+        color
+    }
+
+    /// ハイウェイを作り始めるまで進める
+    ///
+    /// 足元のセルの色とターマイトの状態の並びが`period`ステップ周期で`HIGHWAY_REPEATS`周期以上続き、
+    /// その間に位置がずれていればハイウェイとみなす。`max_steps`ステップ進めても見つからなければ`None`
+    ///
+    /// # Arguments
+    /// * `world` - 盤面
+    /// * `max_steps` - 最大のステップ数
+    /// * `max_period` - 調べる最大の周期
+    pub fn run_until_highway<W: TurmiteWorld>(
+        &mut self,
+        world: &mut W,
+        max_steps: u64,
+        max_period: usize,
+    ) -> Option<Highway> {
+        // 各ステップの(足元の色, 状態)と、盤面で折り返さない位置
+        let mut history = Vec::new();
+        let mut positions = vec![(0i64, 0i64)];
+        // 周期ごとに、直近で`period`ステップ前と同じだったステップが何回続いているか
+        let mut runs = vec![0; max_period + 1];
+        let start_steps = self.steps;
+        for t in 0..max_steps as usize {
+            let state = self.state;
+            let color = self.step(world);
+            history.push((color, state));
+            let (di, dj) = self.direction.offset();
+            let (i, j) = positions[t];
+            positions.push((i + di, j + dj));
+
+            for period in 1..(max_period + 1).min(t + 1) {
+                if history[t] != history[t - period] {
+                    runs[period] = 0;
+                    continue;
+                }
+                runs[period] += 1;
+                if runs[period] >= HIGHWAY_REPEATS * period {
+                    let (i, j) = positions[t + 1];
+                    let (pi, pj) = positions[t + 1 - period];
+                    if (i - pi, j - pj) != (0, 0) {
+                        return Some(Highway {
+                            start: start_steps + (t + 1 - runs[period] - period) as u64,
+                            period: period,
+                            displacement: (i - pi, j - pj),
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+}
@@ -3,6 +3,7 @@ extern crate my_alife;
 extern crate ndarray;
 extern crate rand;
 use my_alife::algorithm::game_of_life::game_of_life_in_parallel;
+use my_alife::algorithm::grid::Grid;
 use ndarray::prelude::*;
 use ndarray::{arr2, Array, ShapeBuilder};
 use rand::{thread_rng, Rng};
@@ -56,15 +57,11 @@ fn stride() {
 fn do_thread_handles() {
     let height = 50;
     let width = 50;
-    let mut state: Vec<Vec<u8>> = Vec::with_capacity(height);
+    let mut state = Grid::new(height, width);
     let mut rng = thread_rng();
-    for i in 0..height {
-        let mut inner: Vec<u8> = Vec::new();
-        for j in 0..width {
-            inner.push(rng.gen_range(0, 2));
-        }
-        state.push(inner);
+    for cell in state.cells_mut() {
+        *cell = rng.gen_range(0, 2);
     }
     let arc = Arc::new(state);
-    game_of_life_in_parallel(arc, height, width);
+    game_of_life_in_parallel(arc);
 }
@@ -1,5 +1,7 @@
+use algorithm::boundary::Boundary;
+use algorithm::generalized_cellular_automata::CellularAutomataRule;
 use failure;
-use ndarray::{Array1, Array2};
+use ndarray::{Array1, Array2, ArrayView1};
 use visualizer::matrix_visualizer::{Matrix, MatrixVisualizer};
 use visualizer::WindowStatus;
 
@@ -62,17 +64,55 @@ impl ArrayVisualizer {
             }
 
             update_fn(&mut initial_state, rule, self.history_size);
-            self.update_matrix(&initial_state.0);
+            self.update_matrix(initial_state.0.view(), 2);
             self.matrix_visualizer.draw(&self.matrix)?;
             window_status = self.matrix_visualizer.hadling_event();
         }
         Ok(())
     }
 
-    fn update_matrix(&mut self, array: &Array1<u32>) {
+    /// `CellularAutomataRule`を実装したルールでメインループを回す
+    ///
+    /// 状態数が3以上のルールでも、状態0を白、最大の状態を黒とした濃淡で表示する。
+    /// `Boundary::Open`で空間が広がっても、表示するのは初期状態と同じ範囲
+    ///
+    /// # Arguments
+    /// * `initail_state` - 初期状態
+    /// * `rule` - 状態を更新するルール
+    /// * `boundary` - 境界条件
+    pub fn draw_loop_by_rule<R>(
+        mut self,
+        mut initial_state: (Array1<u32>, Array1<u32>),
+        rule: R,
+        boundary: Boundary,
+    ) -> Result<(), failure::Error>
+    where
+        R: CellularAutomataRule,
+    {
+        let mut window_status = WindowStatus::Open;
+        let width = initial_state.0.len();
+        // 初期状態の左端が今の配列のどこにあるか
+        let mut origin = 0;
+
+        // main loop
+        loop {
+            if window_status == WindowStatus::Close {
+                break;
+            }
+
+            origin += rule.update_with_boundary(&mut initial_state, boundary);
+            self.update_matrix(initial_state.0.slice(s![origin..origin + width]), rule.states());
+            self.matrix_visualizer.draw(&self.matrix)?;
+            window_status = self.matrix_visualizer.hadling_event();
+        }
+        Ok(())
+    }
+
+    fn update_matrix(&mut self, array: ArrayView1<u32>, states: u32) {
+        let max_state = (states - 1) as f32;
         self.matrix
             .slice_mut(s![self.time_index, ..])
-            .assign(&(1.0 - array.map(|e| *e as f32)));
+            .assign(&(1.0 - array.map(|e| *e as f32 / max_state)));
         self.time_index = (self.time_index + 1) % self.history_size;
     }
 }
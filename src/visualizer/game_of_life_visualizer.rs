@@ -1,26 +1,47 @@
 use algorithm::game_of_life::game_of_life_by_rayon;
+use algorithm::grid::Grid;
+use algorithm::sparse_life::{SparseLife, Viewport};
 use failure;
-use ndarray::prelude::*;
+use glium::glutin::VirtualKeyCode;
 use rand::{thread_rng, Rng};
 use std::mem;
 use std::sync::Arc;
-use visualizer::matrix_visualizer::MatrixVisualizer;
-use visualizer::WindowStatus;
-const WIDTH: usize = 50;
-const HEIGHT: usize = WIDTH;
-
-pub type Matrix = Vec<Vec<u8>>;
+use std::time::{Duration, Instant};
+use visualizer::matrix_visualizer::{Matrix, MatrixVisualizer};
+use visualizer::{UserInput, WindowStatus};
+/// `new`で作る盤面の横のセルの数
+const DEFAULT_WIDTH: usize = 50;
+/// `new`で作る盤面の縦のセルの数
+const DEFAULT_HEIGHT: usize = DEFAULT_WIDTH;
+/// `new`で作る盤面のセルが生きている確率
+const DEFAULT_DENSITY: f64 = 0.5;
+/// ↑キーで世代を進める間隔を短くしたとき、これより短ければ間隔を0(毎フレーム)にする
+const MIN_INTERVAL_MILLIS: u64 = 16;
+/// ↓キーで長くできる世代を進める間隔の上限
+const MAX_INTERVAL_MILLIS: u64 = 2048;
+/// `draw_loop_parallel_by_channel`で、描画されるのを待っていられる状態の数
+const FRAME_QUEUE_SIZE: usize = 2;
 
 /// 2次元配列を用いてlife gameをvisualizeする構造体
+///
+/// どの`draw_loop*`でも、ウィンドウで次の操作ができる
+/// * Space - 一時停止・再開
+/// * N, → - 一時停止中に1世代だけ進める
+/// * ↑, ↓ - 世代を進める間隔を短く・長くする(最初は間隔なしで毎フレーム進める)
+/// * C - 全てのセルを死んだ状態にする
+/// * R - ランダムな状態にする
+/// * 左クリック - 一時停止中にクリックしたセルの生死を切り替える
 pub struct GameOfLifeVisualizer {
     matrix_visualizer: MatrixVisualizer,
-    state: Matrix,
-    next_state: Matrix,
+    state: Grid,
+    next_state: Grid,
 }
 
 impl GameOfLifeVisualizer {
     /// GameOfLifeVisualizerインスタンスを生成する
     ///
+    /// 50x50の盤面の各セルを毎回違うseedでランダムに0か1にする
+    ///
     /// # Arguments
     /// * `title` - ウィンドウに表示するタイトル
     /// * `vertex_glsl_path` - バーテックスシェーダーのファイルを格納しているpath
@@ -29,18 +50,55 @@ impl GameOfLifeVisualizer {
         title: &str,
         vertex_glsl_path: &str,
         faragment_glsl_path: &str,
+    ) -> Result<GameOfLifeVisualizer, failure::Error> {
+        Self::with_random_state(
+            title,
+            vertex_glsl_path,
+            faragment_glsl_path,
+            (DEFAULT_HEIGHT, DEFAULT_WIDTH),
+            DEFAULT_DENSITY,
+            None,
+        )
+    }
+
+    /// 盤面の大きさと初期状態の乱数を指定してGameOfLifeVisualizerインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `title` - ウィンドウに表示するタイトル
+    /// * `vertex_glsl_path` - バーテックスシェーダーのファイルを格納しているpath
+    /// * `grafic_glsl_path` - グラフィックシェーダーのファイルを格納しているpath
+    /// * `(height, width)` - セルの縦と横の数。縦と横は違っていてもよい
+    /// * `density` - 各セルが生きている確率
+    /// * `seed` - 乱数のseed。`None`なら毎回違う初期状態になる
+    pub fn with_random_state(
+        title: &str,
+        vertex_glsl_path: &str,
+        faragment_glsl_path: &str,
+        (height, width): (usize, usize),
+        density: f64,
+        seed: Option<usize>,
+    ) -> Result<GameOfLifeVisualizer, failure::Error> {
+        let state = random_grid(height, width, density, seed);
+        Self::from_grid(title, vertex_glsl_path, faragment_glsl_path, state)
+    }
+
+    /// 初期状態を指定してGameOfLifeVisualizerインスタンスを生成する
+    ///
+    /// `life_pattern::Pattern`で読み込んだパターンを置いたGridを渡せば、そのパターンから始められる
+    ///
+    /// # Arguments
+    /// * `title` - ウィンドウに表示するタイトル
+    /// * `vertex_glsl_path` - バーテックスシェーダーのファイルを格納しているpath
+    /// * `grafic_glsl_path` - グラフィックシェーダーのファイルを格納しているpath
+    /// * `state` - 初期状態
+    pub fn from_grid(
+        title: &str,
+        vertex_glsl_path: &str,
+        faragment_glsl_path: &str,
+        state: Grid,
     ) -> Result<GameOfLifeVisualizer, failure::Error> {
         let matrix_visualizer = MatrixVisualizer::new(title, vertex_glsl_path, faragment_glsl_path)?;
-        let mut rng = thread_rng();
-        let mut state: Vec<Vec<u8>> = Vec::with_capacity(HEIGHT);
-        for _i in 0..HEIGHT {
-            let mut inner: Vec<u8> = Vec::new();
-            for _j in 0..WIDTH {
-                inner.push(rng.gen_range(0, 2));
-            }
-            state.push(inner);
-        }
-        let next_state = vec![vec![0; WIDTH]; HEIGHT];
+        let next_state = Grid::new(state.height(), state.width());
         Ok(GameOfLifeVisualizer {
             matrix_visualizer: matrix_visualizer,
             state: state,
@@ -54,11 +112,11 @@ impl GameOfLifeVisualizer {
     /// * `unpdate_fn` - 描画する状態をどのように変更するかの関数
     ///
     /// # iterator
-    /// 1. Vec<Vec<u8>>をVec<u8>の形にしないとndarrayの形に変換できない
-    ///   * [[0,1,2],[3,4,5],[6,7,8]] -> [0,1,2,3,4,5,6,7,8,9]にする。
-    ///   * from_shape_vec((3,3) [0,1,2,3,4,5,6,7,8,9])すると、3つずつ取っていく
+    /// 1. Gridは全てのセルを1つのVec<u8>に行ごとに並べて持っているので、そのままndarrayの形に変換できる
+    ///   * from_shape_vec((2, 3), [0, 1, 2, 3, 4, 5])すると、3つずつ取って2行3列にする
+    ///   * なので`(height, width)`の順に渡す。`(width, height)`と逆にすると縦横が違う盤面が崩れる
     /// 2. stateは0だと死(白)、1だと生(黒)であるが、visualizer的には0だと黒、1だと白に表示される
-    /// 反転するためにmapの中で変換している
+    /// 反転するためにmapv_inplaceの中で変換している
     /// https://doc.rust-lang.org/book/second-edition/ch13-02-iterators.html
     /// ## iteratorのよくある使い方
     /// 1. iterator Traitを実装している型のデータにinto_iter(), iter(), iter_mut()を使う(それぞれmove, borrow, mutable borrowに相当する)
@@ -76,9 +134,10 @@ impl GameOfLifeVisualizer {
     ///   * collectは何らかのiteratorを何らかのcollection型にする
     pub fn draw_loop<F>(mut self, mut update_fn: F) -> Result<(), failure::Error>
     where
-        F: FnMut(&mut Matrix, &mut Matrix, usize, usize),
+        F: FnMut(&mut Grid, &mut Grid),
     {
         let mut window_status = WindowStatus::Open;
+        let mut controls = Controls::new();
 
         // main loop
         loop {
@@ -86,20 +145,25 @@ impl GameOfLifeVisualizer {
                 break;
             }
 
-            update_fn(&mut self.state, &mut self.next_state, HEIGHT, WIDTH);
+            if controls.should_update() {
+                update_fn(&mut self.state, &mut self.next_state);
+            }
 
-            let state_for_show = self.state.iter().flatten().map(|e| 1.0 - *e as f32).collect::<Vec<_>>();
-            self.matrix_visualizer
-                .draw(&Array::from_shape_vec((WIDTH, HEIGHT), state_for_show)?)?;
-            window_status = self.matrix_visualizer.hadling_event();
+            self.matrix_visualizer.draw(&state_for_show(&self.state))?;
+            let (status, inputs) = self.matrix_visualizer.handling_event_with_inputs();
+            window_status = status;
+            for edit in controls.handle(&inputs, self.state.dims()) {
+                apply_edit(&mut self.state, edit);
+            }
         }
         Ok(())
     }
     pub fn draw_loop_parallel_by_rayon<F>(mut self, mut update_fn: F) -> Result<(), failure::Error>
     where
-        F: FnMut(&Matrix, usize, usize) -> Matrix,
+        F: FnMut(&Grid) -> Grid,
     {
         let mut window_status = WindowStatus::Open;
+        let mut controls = Controls::new();
 
         // main loop
         loop {
@@ -107,65 +171,190 @@ impl GameOfLifeVisualizer {
                 break;
             }
 
-            self.next_state = update_fn(&self.state, HEIGHT, WIDTH);
-
-            mem::swap(&mut self.state, &mut self.next_state);
+            if controls.should_update() {
+                self.next_state = update_fn(&self.state);
+                mem::swap(&mut self.state, &mut self.next_state);
+            }
             // collect::<HashMap<_, _>>()とか、collect::<Result<u8, _>>とか、collect::<String>とか。
             // let hoge: String = iterator.collect();みたいに変数側で指定してもよいし、
             // let hoge = iterator.collect();
             // pass_string(hoge);
             // のように型推論で型指定を省略もできる
-            // ↓だとmap内の処理で新しい要素(u8)を作ってそれのvecを作っている。mapの返り値がeを参照しているとcollectが返す値もVec<&u8>みたいになるはず。
-            let state_for_show = self.state.iter().flatten().map(|e| 1.0 - *e as f32).collect::<Vec<_>>();
-            self.matrix_visualizer
-                .draw(&Array::from_shape_vec((WIDTH, HEIGHT), state_for_show)?)?;
-            window_status = self.matrix_visualizer.hadling_event();
+            self.matrix_visualizer.draw(&state_for_show(&self.state))?;
+            let (status, inputs) = self.matrix_visualizer.handling_event_with_inputs();
+            window_status = status;
+            for edit in controls.handle(&inputs, self.state.dims()) {
+                apply_edit(&mut self.state, edit);
+            }
+        }
+        Ok(())
+    }
+
+    /// 端のない盤面(SparseLife)のうち、viewportの範囲だけを表示するメインループ
+    ///
+    /// # Arguments
+    /// * `life` - 端のない盤面
+    /// * `viewport` - 表示する範囲
+    /// * `follow` - trueなら毎世代viewportを生きているセルの中心に動かす
+    pub fn draw_loop_unbounded(
+        mut self,
+        mut life: SparseLife,
+        mut viewport: Viewport,
+        follow: bool,
+    ) -> Result<(), failure::Error> {
+        let mut window_status = WindowStatus::Open;
+        let mut controls = Controls::new();
+
+        // main loop
+        loop {
+            if window_status == WindowStatus::Close {
+                break;
+            }
+
+            if controls.should_update() {
+                life.step();
+                if follow {
+                    viewport.follow(&life);
+                }
+            }
+
+            self.matrix_visualizer.draw(&state_for_show(&life.to_grid(&viewport)))?;
+            let (status, inputs) = self.matrix_visualizer.handling_event_with_inputs();
+            window_status = status;
+            // 編集はviewportの範囲に対して行う
+            for edit in controls.handle(&inputs, (viewport.height, viewport.width)) {
+                match edit {
+                    Edit::Clear => {
+                        let cells = life.cells().iter().cloned().collect::<Vec<_>>();
+                        for (i, j) in cells {
+                            life.set(i, j, false);
+                        }
+                    }
+                    Edit::Randomize => {
+                        let grid = random_grid(viewport.height, viewport.width, DEFAULT_DENSITY, None);
+                        for (i, row) in grid.rows().enumerate() {
+                            for (j, cell) in row.iter().enumerate() {
+                                life.set(viewport.top + i as i64, viewport.left + j as i64, *cell != 0);
+                            }
+                        }
+                    }
+                    Edit::Toggle(i, j) => {
+                        let (i, j) = (viewport.top + i as i64, viewport.left + j as i64);
+                        let alive = life.get(i, j) == 0;
+                        life.set(i, j, alive);
+                    }
+                }
+            }
         }
         Ok(())
     }
 
     /// ライフゲームの計算用のスレッドを描画用のスレッド(main thread)と分ける
-    /// * 計算用スレッドでループを回し、1ループ毎にメインスレッドに計算結果を送る
+    ///
+    /// `draw_loop_parallel_by_channel_with_steps(1)`と同じ
+    pub fn draw_loop_parallel_by_channel(self) -> Result<(), failure::Error> {
+        self.draw_loop_parallel_by_channel_with_steps(1)
+    }
+
+    /// ライフゲームの計算用のスレッドを描画用のスレッド(main thread)と分ける
+    /// * 計算用スレッドでループを回し、`steps_per_frame`世代進める毎にメインスレッドに計算結果を送る
     /// * thread間通信には一方通行のchannelを用いる
     /// * Producer(計算スレッド)-Consumer(描画スレッド)パターン
     ///   * https://doc.rust-lang.org/std/sync/mpsc/index.html
     ///   * Producerは複数いても良いがConsumerは一人のみなのでMultiProducerSingleConsumer(mpsc)
-    pub fn draw_loop_parallel_by_channel(mut self) -> Result<(), failure::Error> {
-        use std::sync::mpsc::channel;
-        use std::thread::spawn;
+    /// * 計算結果のchannelは`sync_channel`で、`FRAME_QUEUE_SIZE`個までしか溜められない
+    ///   * 溜まっているとProducerの`send`はConsumerが受け取るまでblockする(backpressure)ので、メモリが増え続けない
+    ///   * Consumerは溜まっている中で最新の状態だけを描画し、古い状態は捨てる
+    /// * キーボード・マウスの操作は逆向きのchannelで計算用スレッドに送り、計算用スレッドで盤面に反映する
+    /// * windowを閉じると両方のchannelを閉じる。計算用スレッドは`send`/`try_recv`の失敗で気付いて終わるので、それを待つ(join)
+    ///
+    /// # Arguments
+    /// * `steps_per_frame` - 1回描画する毎に進める世代数(一時停止中に1世代ずつ進める操作では1世代)
+    pub fn draw_loop_parallel_by_channel_with_steps(mut self, steps_per_frame: usize) -> Result<(), failure::Error> {
+        use std::sync::mpsc::{channel, sync_channel, TryRecvError};
+        use std::thread::{sleep, spawn};
         let mut window_status = WindowStatus::Open;
-        let (sender, receiver) = channel();
+        let (sender, receiver) = sync_channel(FRAME_QUEUE_SIZE);
+        let (input_sender, input_receiver) = channel::<Vec<UserInput>>();
         let mut state = self.state.clone();
 
         // calculation thread
-        let _handle = spawn(move || loop {
-            let mut new_state = game_of_life_by_rayon(&state, HEIGHT, WIDTH);
-            // channelにデータを送っている
-            // データを送るときは所有権ごと送ってしまうので、cloneしておかないと次回のloopのときにstateが使えなくなる
-            // 所有権ごと送ることでthread safeを実現している(writableなユーザーが同時に一人しか存在できない)
-            let _result = sender.send(new_state.clone());
-            mem::swap(&mut new_state, &mut state);
+        let handle = spawn(move || {
+            let mut controls = Controls::new();
+            loop {
+                let mut changed = false;
+                // 溜まっている操作を全て反映する。描画スレッドが終わってchannelが切れたら計算スレッドも終わる
+                loop {
+                    match input_receiver.try_recv() {
+                        Ok(inputs) => {
+                            for edit in controls.handle(&inputs, state.dims()) {
+                                apply_edit(&mut state, edit);
+                                changed = true;
+                            }
+                        }
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => return,
+                    }
+                }
+                if controls.should_update() {
+                    let steps = if controls.paused { 1 } else { steps_per_frame };
+                    for _ in 0..steps {
+                        let mut new_state = game_of_life_by_rayon(&state);
+                        mem::swap(&mut new_state, &mut state);
+                    }
+                    changed = true;
+                }
+                if changed {
+                    // channelにデータを送っている
+                    // データを送るときは所有権ごと送ってしまうので、cloneしておかないと次回のloopのときにstateが使えなくなる
+                    // 所有権ごと送ることでthread safeを実現している(writableなユーザーが同時に一人しか存在できない)
+                    // 受け取る側(receiver)がdropされていたらErrが返るので終わる
+                    if sender.send(state.clone()).is_err() {
+                        return;
+                    }
+                } else {
+                    // 一時停止中などに空回りしないように少し待つ
+                    sleep(Duration::from_millis(1));
+                }
+            }
         });
 
         // main thread
-        // channelを受信するまでblockingしている
-        for state in receiver {
+        // 届いている中で最新の状態を描画する。届いていなければ前の状態をもう一度描画する
+        let mut result = Ok(());
+        loop {
             if window_status == WindowStatus::Close {
                 break;
             }
-            let state_for_show = state.iter().flatten().map(|e| 1.0 - *e as f32).collect::<Vec<_>>();
-            self.matrix_visualizer
-                .draw(&Array::from_shape_vec((WIDTH, HEIGHT), state_for_show)?)?;
-            window_status = self.matrix_visualizer.hadling_event();
+            if let Some(state) = receiver.try_iter().last() {
+                self.state = state;
+            }
+            if let Err(error) = self.matrix_visualizer.draw(&state_for_show(&self.state)) {
+                result = Err(error);
+                break;
+            }
+            let (status, inputs) = self.matrix_visualizer.handling_event_with_inputs();
+            window_status = status;
+            if !inputs.is_empty() && input_sender.send(inputs).is_err() {
+                break;
+            }
         }
-        Ok(())
+
+        // channelを閉じて、計算用スレッドが終わるのを待つ
+        drop(receiver);
+        drop(input_sender);
+        handle
+            .join()
+            .map_err(|_| failure::err_msg("the calculation thread of Game of Life panicked"))?;
+        result
     }
 }
 
+/// `GameOfLifeVisualizer`と同じ操作ができる、`Arc<Grid>`を計算用のスレッドに渡すvisualizer
 pub struct GameOfLifeVisualizerParallel {
     matrix_visualizer: MatrixVisualizer,
-    state: Arc<Matrix>,
-    next_state: Arc<Matrix>,
+    state: Arc<Grid>,
+    next_state: Arc<Grid>,
 }
 
 impl GameOfLifeVisualizerParallel {
@@ -173,18 +362,41 @@ impl GameOfLifeVisualizerParallel {
         title: &str,
         vertex_glsl_path: &str,
         faragment_glsl_path: &str,
+    ) -> Result<GameOfLifeVisualizerParallel, failure::Error> {
+        Self::with_random_state(
+            title,
+            vertex_glsl_path,
+            faragment_glsl_path,
+            (DEFAULT_HEIGHT, DEFAULT_WIDTH),
+            DEFAULT_DENSITY,
+            None,
+        )
+    }
+
+    /// 盤面の大きさと初期状態の乱数を指定してGameOfLifeVisualizerParallelインスタンスを生成する
+    ///
+    /// 引数は`GameOfLifeVisualizer::with_random_state`と同じ
+    pub fn with_random_state(
+        title: &str,
+        vertex_glsl_path: &str,
+        faragment_glsl_path: &str,
+        (height, width): (usize, usize),
+        density: f64,
+        seed: Option<usize>,
+    ) -> Result<GameOfLifeVisualizerParallel, failure::Error> {
+        let state = random_grid(height, width, density, seed);
+        Self::from_grid(title, vertex_glsl_path, faragment_glsl_path, state)
+    }
+
+    /// 初期状態を指定してGameOfLifeVisualizerParallelインスタンスを生成する
+    pub fn from_grid(
+        title: &str,
+        vertex_glsl_path: &str,
+        faragment_glsl_path: &str,
+        state: Grid,
     ) -> Result<GameOfLifeVisualizerParallel, failure::Error> {
         let matrix_visualizer = MatrixVisualizer::new(title, vertex_glsl_path, faragment_glsl_path)?;
-        let mut rng = thread_rng();
-        let mut state: Vec<Vec<u8>> = Vec::with_capacity(HEIGHT);
-        for _i in 0..HEIGHT {
-            let mut inner: Vec<u8> = Vec::new();
-            for _j in 0..WIDTH {
-                inner.push(rng.gen_range(0, 2));
-            }
-            state.push(inner);
-        }
-        let next_state = vec![vec![0; WIDTH]; HEIGHT];
+        let next_state = Grid::new(state.height(), state.width());
         Ok(GameOfLifeVisualizerParallel {
             matrix_visualizer: matrix_visualizer,
             state: Arc::new(state),
@@ -193,9 +405,10 @@ impl GameOfLifeVisualizerParallel {
     }
     pub fn draw_loop_parallel<F>(mut self, mut update_fn: F) -> Result<(), failure::Error>
     where
-        F: FnMut(Arc<Matrix>, usize, usize) -> Matrix,
+        F: FnMut(Arc<Grid>) -> Grid,
     {
         let mut window_status = WindowStatus::Open;
+        let mut controls = Controls::new();
 
         // main loop
         loop {
@@ -203,14 +416,125 @@ impl GameOfLifeVisualizerParallel {
                 break;
             }
 
-            self.next_state = Arc::new(update_fn(self.state.clone(), HEIGHT, WIDTH));
+            if controls.should_update() {
+                self.next_state = Arc::new(update_fn(self.state.clone()));
+                mem::swap(&mut self.state, &mut self.next_state);
+            }
 
-            mem::swap(&mut self.state, &mut self.next_state);
-            let state_for_show = self.state.iter().flatten().map(|e| 1.0 - *e as f32).collect::<Vec<_>>();
-            self.matrix_visualizer
-                .draw(&Array::from_shape_vec((WIDTH, HEIGHT), state_for_show)?)?;
-            window_status = self.matrix_visualizer.hadling_event();
+            self.matrix_visualizer.draw(&state_for_show(&self.state))?;
+            let (status, inputs) = self.matrix_visualizer.handling_event_with_inputs();
+            window_status = status;
+            for edit in controls.handle(&inputs, self.state.dims()) {
+                // 計算用のスレッドが持っていたArcは計算が終わったときにdropされているので、cloneせずに書き換えられる
+                apply_edit(Arc::make_mut(&mut self.state), edit);
+            }
         }
         Ok(())
     }
 }
+
+/// 盤面への編集。座標は表示しているGridでの`(i, j)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edit {
+    /// 全てのセルを死んだ状態にする
+    Clear,
+    /// ランダムな状態にする
+    Randomize,
+    /// セルの生死を切り替える
+    Toggle(usize, usize),
+}
+
+/// キーボード・マウスの操作を受け取って、一時停止や世代を進める間隔などの再生の状態を持つ
+struct Controls {
+    paused: bool,
+    /// 一時停止中に1世代だけ進めるよう頼まれている
+    step_requested: bool,
+    /// 世代を進める間隔
+    interval: Duration,
+    last_update: Instant,
+}
+
+impl Controls {
+    fn new() -> Controls {
+        Controls {
+            paused: false,
+            step_requested: false,
+            interval: Duration::from_millis(0),
+            last_update: Instant::now(),
+        }
+    }
+
+    /// 操作を再生の状態に反映し、盤面への編集を返す
+    ///
+    /// # Arguments
+    /// * `inputs` - 前のフレームからの操作
+    /// * `(height, width)` - 表示している盤面の大きさ
+    fn handle(&mut self, inputs: &[UserInput], (height, width): (usize, usize)) -> Vec<Edit> {
+        let mut edits = vec![];
+        for input in inputs {
+            match *input {
+                UserInput::KeyPressed(VirtualKeyCode::Space) => self.paused = !self.paused,
+                UserInput::KeyPressed(VirtualKeyCode::N) | UserInput::KeyPressed(VirtualKeyCode::Right) => {
+                    self.step_requested = self.paused
+                }
+                UserInput::KeyPressed(VirtualKeyCode::Up) => {
+                    self.interval = if self.interval <= Duration::from_millis(MIN_INTERVAL_MILLIS) {
+                        Duration::from_millis(0)
+                    } else {
+                        self.interval / 2
+                    }
+                }
+                UserInput::KeyPressed(VirtualKeyCode::Down) => {
+                    self.interval = if self.interval < Duration::from_millis(MIN_INTERVAL_MILLIS) {
+                        Duration::from_millis(MIN_INTERVAL_MILLIS)
+                    } else {
+                        (self.interval * 2).min(Duration::from_millis(MAX_INTERVAL_MILLIS))
+                    }
+                }
+                UserInput::KeyPressed(VirtualKeyCode::C) => edits.push(Edit::Clear),
+                UserInput::KeyPressed(VirtualKeyCode::R) => edits.push(Edit::Randomize),
+                UserInput::Clicked { x, y } if self.paused && (0.0..1.0).contains(&x) && (0.0..1.0).contains(&y) => {
+                    edits.push(Edit::Toggle((y * height as f64) as usize, (x * width as f64) as usize))
+                }
+                _ => {}
+            }
+        }
+        edits
+    }
+
+    /// このフレームで1世代進めるかどうか
+    fn should_update(&mut self) -> bool {
+        let update = if self.paused {
+            mem::replace(&mut self.step_requested, false)
+        } else {
+            self.last_update.elapsed() >= self.interval
+        };
+        if update {
+            self.last_update = Instant::now();
+        }
+        update
+    }
+}
+
+/// 盤面に編集を反映する
+fn apply_edit(state: &mut Grid, edit: Edit) {
+    let (height, width) = state.dims();
+    match edit {
+        Edit::Clear => *state = Grid::new(height, width),
+        Edit::Randomize => *state = random_grid(height, width, DEFAULT_DENSITY, None),
+        Edit::Toggle(i, j) => state[(i, j)] = if state[(i, j)] == 0 { 1 } else { 0 },
+    }
+}
+
+/// 各セルが確率`density`で1になるGrid。`seed`が`None`ならseedもランダムに選ぶ
+fn random_grid(height: usize, width: usize, density: f64, seed: Option<usize>) -> Grid {
+    let seed = seed.unwrap_or_else(|| thread_rng().gen());
+    Grid::random(height, width, density, seed)
+}
+
+/// 生(1)を黒、死(0)を白で表示するために反転したMatrix
+fn state_for_show(state: &Grid) -> Matrix<f32> {
+    let mut matrix = state.to_matrix();
+    matrix.mapv_inplace(|e| 1.0 - e);
+    matrix
+}
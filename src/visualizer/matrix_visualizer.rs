@@ -4,7 +4,7 @@ use ndarray::{ArrayBase, Dim, OwnedRepr};
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
-use visualizer::WindowStatus;
+use visualizer::{UserInput, WindowStatus};
 
 /// 直交座標系(XY座標系)を用いてvisualizeする構造体
 pub struct MatrixVisualizer {
@@ -13,6 +13,8 @@ pub struct MatrixVisualizer {
     vertex_buffer: VertexBuffer<Vertex>,
     indices: index::NoIndices,
     display: Display,
+    /// ウィンドウ内のマウスカーソルの位置(x, y)
+    cursor_position: (f64, f64),
 }
 
 impl MatrixVisualizer {
@@ -58,6 +60,7 @@ impl MatrixVisualizer {
             vertex_buffer: vertex_buffer,
             indices: index::NoIndices(index::PrimitiveType::TrianglesList),
             display: display,
+            cursor_position: (0.0, 0.0),
         })
     }
 
@@ -382,7 +385,17 @@ impl MatrixVisualizer {
     /// [資料](https://doc.rust-lang.org/book/2018-edition/ch04-02-references-and-borrowing.html)
     /// [日本語訳](https://github.com/hazama-yuinyan/book/blob/master/second-edition/src/ch04-02-references-and-borrowing.md)
     pub fn draw(&self, matrix: &Matrix<f32>) -> Result<(), failure::Error> {
-        let image = make_texture_image(matrix);
+        self.draw_image(make_texture_image(matrix))
+    }
+
+    /// 各要素をRGBの色として描画する
+    ///
+    /// 赤・緑・青の全てを使うので、フラグメントシェーダーには`res/shaders/matrix_visualizer_color_fragment.glsl`を使う
+    pub fn draw_colors(&self, matrix: &Matrix<[u8; 3]>) -> Result<(), failure::Error> {
+        self.draw_image(make_color_texture_image(matrix))
+    }
+
+    fn draw_image(&self, image: texture::RawImage2d<u8>) -> Result<(), failure::Error> {
         let texture = texture::Texture2d::new(&self.display, image);
         let mut target = self.display.draw();
         target.clear_color(1.0, 0.0, 0.0, 1.0);
@@ -399,7 +412,21 @@ impl MatrixVisualizer {
 
     /// event handler
     pub fn hadling_event(&mut self) -> WindowStatus {
+        self.handling_event_with_inputs().0
+    }
+
+    /// event handler
+    ///
+    /// windowの状態に加えて、前回呼んでから押されたキーとクリックされた位置を返す
+    pub fn handling_event_with_inputs(&mut self) -> (WindowStatus, Vec<UserInput>) {
         let mut status = WindowStatus::Open;
+        let mut inputs = vec![];
+        let window_size = self
+            .display
+            .gl_window()
+            .get_inner_size()
+            .map_or((1.0, 1.0), |size| (size.width, size.height));
+        let cursor_position = &mut self.cursor_position;
         self.events_loop.poll_events(|event| {
             // matchさせたいパターンが1つしかない場合、if let 形式で書ける
             // matchでやると
@@ -418,24 +445,40 @@ impl MatrixVisualizer {
                         glutin::KeyboardInput { // 構造体の各fieldをdestructuringできる
                             virtual_keycode, // virtual_keycode: virtual_keycode を省略形
                             modifiers, // modifiers: my_modifiers の様に省略しないで別名をつけても良い
-                            .. // 使わないfieldのscancode: _, state: _, を省略できる
+                            state, // キーを押したときと離したときの両方でeventが来る
+                            .. // 使わないfieldのscancode: _, を省略できる
                         } => match (virtual_keycode, modifiers) { // 複数のパターンマッチにはタプルを使う
                             #[cfg(target_os = "linux")] // conditional compile https://doc.rust-lang.org/reference/attributes.html#conditional-compilation
-                            (Some(glutin::VirtualKeyCode::W), glutin::ModifiersState { ctrl, .. }) => {
-                              if ctrl { status = WindowStatus::Close }
+                            (Some(glutin::VirtualKeyCode::W), glutin::ModifiersState { ctrl: true, .. }) => {
+                              status = WindowStatus::Close
                             },
                             #[cfg(target_os = "macos")]
-                            (Some(glutin::VirtualKeyCode::W), glutin::ModifiersState { logo, .. }) => {
-                              if logo { status = WindowStatus::Close }
+                            (Some(glutin::VirtualKeyCode::W), glutin::ModifiersState { logo: true, .. }) => {
+                              status = WindowStatus::Close
+                            },
+                            (Some(key), _) => {
+                              if state == glutin::ElementState::Pressed { inputs.push(UserInput::KeyPressed(key)) }
                             },
-                            (_, _) => {}
+                            (None, _) => {}
                         },
                     },
+                    glutin::WindowEvent::CursorMoved { position, .. } => {
+                        *cursor_position = (position.x, position.y);
+                    }
+                    glutin::WindowEvent::MouseInput {
+                        state: glutin::ElementState::Pressed,
+                        button: glutin::MouseButton::Left,
+                        ..
+                    } => inputs.push(UserInput::Clicked {
+                        // ウィンドウの大きさに対する割合にする
+                        x: cursor_position.0 / window_size.0,
+                        y: cursor_position.1 / window_size.1,
+                    }),
                     _ => {}
                 }
             };
         });
-        return status;
+        (status, inputs)
     }
 }
 
@@ -450,6 +493,17 @@ struct Vertex {
 }
 implement_vertex!(Vertex, a_position, a_texcoord);
 
+/// 各要素がRGBの色を意味する2次元配列から画像データを生成する
+fn make_color_texture_image<'a>(u: &Matrix<[u8; 3]>) -> texture::RawImage2d<'a, u8> {
+    let mut texture_data = Vec::with_capacity(u.len() * 4);
+    for row in u.outer_iter() {
+        for &[r, g, b] in row.iter() {
+            texture_data.extend_from_slice(&[r, g, b, 255]);
+        }
+    }
+    texture::RawImage2d::from_raw_rgba(texture_data, (u.shape()[1] as u32, u.shape()[0] as u32))
+}
+
 /// 各要素が画素値を意味する2次元配列から画像データを生成する
 fn make_texture_image<'a>(u: &Matrix<f32>) -> texture::RawImage2d<'a, u8> {
     let mut texture_data = Vec::new();
@@ -469,5 +523,6 @@ fn make_texture_image<'a>(u: &Matrix<f32>) -> texture::RawImage2d<'a, u8> {
             texture_data.push(v);
         }
     }
-    texture::RawImage2d::from_raw_rgba(texture_data, (u.shape()[0] as u32, u.shape()[1] as u32))
+    // `u`は(行, 列) = (縦, 横)だが、RawImage2dの大きさは(横, 縦)で渡す
+    texture::RawImage2d::from_raw_rgba(texture_data, (u.shape()[1] as u32, u.shape()[0] as u32))
 }
@@ -4,6 +4,10 @@ pub mod matrix_visualizer;
 /// 一次元配列を用いてvisualizeするためのモジュール
 pub mod array_visualizer;
 pub mod game_of_life_visualizer;
+/// 状態ごとに色を変えて3状態以上の2次元セルラーオートマトンをvisualizeするためのモジュール
+pub mod multi_state_visualizer;
+/// ウィンドウを開かずに1次元配列の時空図を画像として書き出すためのモジュール
+pub mod spacetime_diagram;
 
 /// windowの状態
 #[derive(PartialEq)]
@@ -13,3 +17,17 @@ pub enum WindowStatus {
     /// 閉じている
     Close,
 }
+
+/// windowでのキーボード・マウスの操作
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserInput {
+    /// キーが押された
+    KeyPressed(::glium::glutin::VirtualKeyCode),
+    /// 左ボタンでクリックされた。位置はwindowの左上を(0, 0)、右下を(1, 1)とした割合
+    Clicked {
+        /// 横の位置
+        x: f64,
+        /// 縦の位置
+        y: f64,
+    },
+}
@@ -0,0 +1,119 @@
+use algorithm::grid::Grid;
+use failure;
+use ndarray::Array;
+use visualizer::matrix_visualizer::{Matrix, MatrixVisualizer};
+use visualizer::WindowStatus;
+
+/// Wireworldの状態(空、電子の頭、電子の尾、導線)ごとの色
+pub const WIREWORLD_PALETTE: [[u8; 3]; 4] = [[0, 0, 0], [64, 128, 255], [255, 64, 32], [255, 200, 0]];
+
+/// Langtonのループの状態(0から7)ごとの色
+pub const LANGTONS_LOOP_PALETTE: [[u8; 3]; 8] = [
+    [0, 0, 0],
+    [0, 0, 255],
+    [255, 0, 0],
+    [0, 255, 0],
+    [255, 255, 0],
+    [255, 0, 255],
+    [255, 255, 255],
+    [0, 255, 255],
+];
+
+/// 3状態以上の2次元セルラーオートマトンを、状態ごとに色を変えてvisualizeする構造体
+///
+/// フラグメントシェーダーには`res/shaders/matrix_visualizer_color_fragment.glsl`を使う
+pub struct MultiStateVisualizer {
+    matrix_visualizer: MatrixVisualizer,
+    state: Grid,
+    next_state: Grid,
+    palette: Vec<[u8; 3]>,
+}
+
+impl MultiStateVisualizer {
+    /// MultiStateVisualizerインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `title` - ウィンドウに表示するタイトル
+    /// * `vertex_glsl_path` - バーテックスシェーダーのファイルを格納しているpath
+    /// * `grafic_glsl_path` - グラフィックシェーダーのファイルを格納しているpath
+    /// * `state` - 初期状態
+    /// * `palette` - 状態`k`のセルを`palette[k]`のRGBの色で描く。範囲外の状態は白で描く
+    pub fn new(
+        title: &str,
+        vertex_glsl_path: &str,
+        faragment_glsl_path: &str,
+        state: Grid,
+        palette: Vec<[u8; 3]>,
+    ) -> Result<MultiStateVisualizer, failure::Error> {
+        let matrix_visualizer = MatrixVisualizer::new(title, vertex_glsl_path, faragment_glsl_path)?;
+        let next_state = Grid::new(state.height(), state.width());
+        Ok(MultiStateVisualizer {
+            matrix_visualizer: matrix_visualizer,
+            state: state,
+            next_state: next_state,
+            palette: palette,
+        })
+    }
+
+    /// メインループ
+    ///
+    /// # Arguments
+    /// * `unpdate_fn` - 描画する状態をどのように変更するかの関数
+    pub fn draw_loop<F>(mut self, mut update_fn: F) -> Result<(), failure::Error>
+    where
+        F: FnMut(&mut Grid, &mut Grid),
+    {
+        let mut window_status = WindowStatus::Open;
+
+        // main loop
+        loop {
+            if window_status == WindowStatus::Close {
+                break;
+            }
+
+            update_fn(&mut self.state, &mut self.next_state);
+
+            self.matrix_visualizer
+                .draw_colors(&state_for_show(&self.state, &self.palette))?;
+            window_status = self.matrix_visualizer.hadling_event();
+        }
+        Ok(())
+    }
+}
+
+/// Generations型のルールの状態ごとの色
+///
+/// 死んだセル(0)は黒、生きたセル(1)は白で、死につつあるセル(2以上)は青から暗くなっていく
+pub fn generations_palette(states: u8) -> Vec<[u8; 3]> {
+    let mut palette = vec![[0, 0, 0], [255, 255, 255]];
+    let dying_states = states.saturating_sub(2) as usize;
+    for k in 0..dying_states {
+        let brightness = 255 - 191 * k / dying_states.max(1);
+        palette.push([0, (brightness / 2) as u8, brightness as u8]);
+    }
+    palette
+}
+
+/// ターマイトが塗るセルの色ごとの色と、最後にターマイト自身の色を加えたもの
+///
+/// 色0は黒で、色1から`colors - 1`は色相を順にずらした色。ターマイト自身(状態`colors`)は白で描く
+pub fn turmite_palette(colors: u8) -> Vec<[u8; 3]> {
+    let mut palette = vec![[0, 0, 0]];
+    for k in 1..colors {
+        let hue = 6.0 * f32::from(k - 1) / f32::from(colors - 1);
+        let channel = |offset: f32| {
+            let distance = ((hue - offset + 6.0) % 6.0 - 3.0).abs();
+            (255.0 * (distance - 1.0).clamp(0.0, 1.0)) as u8
+        };
+        palette.push([channel(0.0), channel(2.0), channel(4.0)]);
+    }
+    palette.push([255, 255, 255]);
+    palette
+}
+
+/// 各セルをpaletteの色にしたMatrix
+fn state_for_show(state: &Grid, palette: &[[u8; 3]]) -> Matrix<[u8; 3]> {
+    Array::from_shape_fn(state.dims(), |(i, j)| {
+        palette.get(state[(i, j)] as usize).cloned().unwrap_or([255, 255, 255])
+    })
+}
@@ -0,0 +1,207 @@
+use algorithm::boundary::Boundary;
+use algorithm::generalized_cellular_automata::CellularAutomataRule;
+use failure;
+use ndarray::{Array1, ArrayView1};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+/// 1次元セルラーオートマトンの時空図を画像として書き出す構造体
+///
+/// `ArrayVisualizer`と違いウィンドウを開かずに、各時刻の状態を上から順に並べた全履歴を
+/// PGM(グレースケール)かPPM(カラー)の画像ファイルにする。ディスプレイのない環境でも使える
+///
+/// # Example
+/// ```
+/// extern crate ndarray;
+/// extern crate my_alife;
+///
+/// use my_alife::algorithm::boundary::Boundary;
+/// use my_alife::visualizer::spacetime_diagram::{gray_colors, SpacetimeDiagram};
+/// use ndarray::Array1;
+///
+/// let mut state = (Array1::<u32>::zeros(5), Array1::<u32>::zeros(5));
+/// state.0[2] = 1;
+/// let mut diagram = SpacetimeDiagram::new(5, 2, gray_colors(2));
+/// diagram.record(&90u8, &mut state, 2, Boundary::Fixed(0));
+/// assert_eq!(diagram.height(), 3);
+///
+/// let mut pgm = Vec::new();
+/// diagram.write_pgm_to(&mut pgm).unwrap();
+/// // ヘッダの後に(5 * 2) * (3 * 2)画素が続く
+/// assert!(pgm.starts_with(b"P5\n10 6\n255\n"));
+/// assert_eq!(pgm.len(), "P5\n10 6\n255\n".len() + 60);
+/// ```
+pub struct SpacetimeDiagram {
+    width: usize,
+    cell_size: usize,
+    colors: Vec<[u8; 3]>,
+    cells: Vec<u32>,
+}
+
+impl SpacetimeDiagram {
+    /// SpacetimeDiagramインスタンスを生成する
+    ///
+    /// # Arguments
+    /// * `width` - 1時刻分のセルの数
+    /// * `cell_size` - 1セルを何画素四方で描くか
+    /// * `colors` - 状態ごとの色(RGB)。状態数より少ない場合、足りない状態は最後の色で描く
+    pub fn new(width: usize, cell_size: usize, colors: Vec<[u8; 3]>) -> SpacetimeDiagram {
+        assert!(cell_size > 0, "cell_size must be 1 or more");
+        assert!(!colors.is_empty(), "colors must not be empty");
+        SpacetimeDiagram {
+            width: width,
+            cell_size: cell_size,
+            colors: colors,
+            cells: Vec::new(),
+        }
+    }
+
+    /// 記録した時刻の数
+    pub fn height(&self) -> usize {
+        if self.width == 0 {
+            0
+        } else {
+            self.cells.len() / self.width
+        }
+    }
+
+    /// 1時刻分の状態を末尾に追加する
+    pub fn push(&mut self, state: ArrayView1<u32>) {
+        assert_eq!(state.len(), self.width, "state must have {} cells", self.width);
+        self.cells.extend(state.iter());
+    }
+
+    /// 同じ大きさの時空図を`columns`列に並べて1枚の時空図にする
+    ///
+    /// 時空図の間は1セル分空けて`separator`の状態で埋める。セルの大きさと色は先頭の時空図のものを使う
+    ///
+    /// # Arguments
+    /// * `diagrams` - 並べる時空図
+    /// * `columns` - 横に並べる数
+    /// * `separator` - 時空図の間を埋める状態
+    pub fn tile(diagrams: &[SpacetimeDiagram], columns: usize, separator: u32) -> SpacetimeDiagram {
+        assert!(!diagrams.is_empty(), "diagrams must not be empty");
+        let (width, height) = (diagrams[0].width, diagrams[0].height());
+        assert!(
+            diagrams.iter().all(|d| d.width == width && d.height() == height),
+            "all diagrams must have the same size"
+        );
+        let columns = columns.max(1).min(diagrams.len());
+        let tiled_width = columns * (width + 1) - 1;
+        let mut tiled = SpacetimeDiagram::new(tiled_width, diagrams[0].cell_size, diagrams[0].colors.clone());
+        for (row_index, row) in diagrams.chunks(columns).enumerate() {
+            if row_index > 0 {
+                tiled.cells.extend(vec![separator; tiled_width]);
+            }
+            for t in 0..height {
+                for (column, diagram) in row.iter().enumerate() {
+                    if column > 0 {
+                        tiled.cells.push(separator);
+                    }
+                    tiled.cells.extend(&diagram.cells[t * width..(t + 1) * width]);
+                }
+                // 最後の行が埋まらない場合は右側を区切りで埋める
+                let filled = row.len() * (width + 1) - 1;
+                tiled.cells.extend(vec![separator; tiled_width - filled]);
+            }
+        }
+        tiled
+    }
+
+    /// 現在の状態を記録してから、`rule`で`steps`回更新しながら各時刻の状態を記録する
+    ///
+    /// `Boundary::Open`で空間が広がっても、記録するのは最初の状態と同じ範囲
+    ///
+    /// # Arguments
+    /// * `rule` - 状態を更新するルール
+    /// * `state` - 現在の状態と次の状態の組
+    /// * `steps` - 更新する回数
+    /// * `boundary` - 境界条件
+    pub fn record<R>(&mut self, rule: &R, state: &mut (Array1<u32>, Array1<u32>), steps: usize, boundary: Boundary)
+    where
+        R: CellularAutomataRule,
+    {
+        let mut origin = 0;
+        self.push(state.0.slice(s![origin..origin + self.width]));
+        for _ in 0..steps {
+            origin += rule.update_with_boundary(state, boundary);
+            self.push(state.0.slice(s![origin..origin + self.width]));
+        }
+    }
+
+    /// 時空図をPGM(P5)形式で`path`に書き出す。色は輝度に変換される
+    pub fn write_pgm<P: AsRef<Path>>(&self, path: P) -> Result<(), failure::Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_pgm_to(&mut writer)
+    }
+
+    /// 時空図をPPM(P6)形式で`path`に書き出す
+    pub fn write_ppm<P: AsRef<Path>>(&self, path: P) -> Result<(), failure::Error> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm_to(&mut writer)
+    }
+
+    /// 時空図をPGM(P5)形式で`writer`に書き出す
+    pub fn write_pgm_to<W: Write>(&self, writer: &mut W) -> Result<(), failure::Error> {
+        let gray = self
+            .colors
+            .iter()
+            .map(|c| ((299 * c[0] as u32 + 587 * c[1] as u32 + 114 * c[2] as u32) / 1000) as u8)
+            .collect::<Vec<_>>();
+        write!(
+            writer,
+            "P5\n{} {}\n255\n",
+            self.width * self.cell_size,
+            self.height() * self.cell_size
+        )?;
+        self.write_pixels(writer, |state| vec![gray[state]])
+    }
+
+    /// 時空図をPPM(P6)形式で`writer`に書き出す
+    pub fn write_ppm_to<W: Write>(&self, writer: &mut W) -> Result<(), failure::Error> {
+        write!(
+            writer,
+            "P6\n{} {}\n255\n",
+            self.width * self.cell_size,
+            self.height() * self.cell_size
+        )?;
+        self.write_pixels(writer, |state| self.colors[state].to_vec())
+    }
+
+    fn write_pixels<W, F>(&self, writer: &mut W, pixel: F) -> Result<(), failure::Error>
+    where
+        W: Write,
+        F: Fn(usize) -> Vec<u8>,
+    {
+        if self.width == 0 {
+            return Ok(());
+        }
+        let last_color = self.colors.len() - 1;
+        for row in self.cells.chunks(self.width) {
+            let mut line = Vec::new();
+            for state in row {
+                let color = pixel((*state as usize).min(last_color));
+                for _ in 0..self.cell_size {
+                    line.extend(&color);
+                }
+            }
+            for _ in 0..self.cell_size {
+                writer.write_all(&line)?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+/// 状態0を白、状態`states - 1`を黒とした濃淡の色を返す(`ArrayVisualizer`と同じ配色)
+pub fn gray_colors(states: u32) -> Vec<[u8; 3]> {
+    let max_state = (states.max(2) - 1) as f32;
+    (0..states.max(2))
+        .map(|state| {
+            let v = ((1.0 - state as f32 / max_state) * 255.0) as u8;
+            [v, v, v]
+        })
+        .collect()
+}